@@ -0,0 +1,53 @@
+use std::path::Path;
+
+// Everything the tool writes into a scanned directory
+pub const DUPLICATES_FOLDER_NAME: &str = "duplicates";
+pub const INDEX_FILE_NAME: &str = "duplicate_files_index.txt";
+pub const JOURNAL_FILE_NAME: &str = "duplicate_files_journal.jsonl";
+pub const CACHE_FILE_NAME: &str = "duplicate_files_cache.json";
+pub const QUARANTINE_MARKER_NAME: &str = ".duplicate_files_quarantine";
+
+const ARTIFACT_FILE_NAMES: &[&str] = &[
+    INDEX_FILE_NAME,
+    JOURNAL_FILE_NAME,
+    CACHE_FILE_NAME,
+    QUARANTINE_MARKER_NAME,
+    // Written while the cache is being saved
+    "duplicate_files_cache.json.tmp",
+];
+
+pub fn is_artifact_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| ARTIFACT_FILE_NAMES.contains(&name))
+}
+
+// A `duplicates` folder only belongs to the tool when it sits at the scan root or next to
+// an index or journal from an earlier run; user folders with the same name are scanned
+pub fn is_duplicates_folder(path: &Path, root_path: &Path) -> bool {
+    if path.file_name() != Some(DUPLICATES_FOLDER_NAME.as_ref()) {
+        return false;
+    }
+
+    let Some(parent) = path.parent() else {
+        return false;
+    };
+
+    parent == root_path
+        || parent.join(INDEX_FILE_NAME).is_file()
+        || parent.join(JOURNAL_FILE_NAME).is_file()
+}
+
+// Quarantine folders are marked when they are first used, so later scans skip them whatever
+// options they run with
+pub fn is_quarantine_folder(path: &Path) -> bool {
+    path.join(QUARANTINE_MARKER_NAME).is_file()
+}
+
+// Per-volume trash folders from the freedesktop.org spec, which deleted duplicates may land in
+// when the scanned root is a mount point
+pub fn is_volume_trash(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name == ".Trash" || name.starts_with(".Trash-"))
+}
@@ -0,0 +1,357 @@
+use crate::duplicate_detector::{DetectionOptions, default_thread_count};
+use crate::file_scanner::ScanOptions;
+use crate::hash_cache::HashCache;
+use crate::hasher::HashAlgorithm;
+use crate::keeper::KeepPolicy;
+use crate::organizer::{DeleteMode, DuplicateAction, SymlinkStyle};
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "duplicate-finder-rs",
+    version,
+    about = "Find identical files and organize them into duplicate groups",
+    long_about = "Find identical files and organize them into duplicate groups.\n\n\
+                  Run without a subcommand to choose the directory interactively."
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Cli {
+    // Checks that clap cannot express on its own
+    pub fn validate(self) -> Result<Self, clap::Error> {
+        let run_args = match &self.command {
+            Some(Command::Scan(args) | Command::Report(args)) => args,
+            Some(Command::Organize(args)) => &args.run,
+            _ => return Ok(self),
+        };
+
+        // Reports are written one after another, so stdout can only take one of them
+        if run_args.stdout_report_count() > 1 {
+            return Err(Cli::command().error(
+                ErrorKind::ArgumentConflict,
+                "only one report can be written to stdout (`-`)",
+            ));
+        }
+
+        Ok(self)
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Find duplicate groups and print them without writing or moving anything
+    Scan(RunArgs),
+    /// Move duplicate files into the `duplicates` folder and write the index
+    Organize(OrganizeArgs),
+    /// Write the duplicate index without moving any files
+    Report(RunArgs),
+    /// Move organized files back to their original locations using the journal
+    #[command(visible_alias = "restore")]
+    Undo(UndoArgs),
+    /// Inspect, prune or clear the persistent hash cache
+    Cache(CacheArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub action: CacheAction,
+
+    /// Directory whose cache is used (defaults to the current directory)
+    #[arg(value_name = "ROOT", global = true)]
+    pub root: Option<String>,
+
+    /// Cache file to use instead of the one in ROOT
+    #[arg(long, value_name = "FILE", global = true)]
+    pub cache_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, Subcommand)]
+pub enum CacheAction {
+    /// Show how many hashes are cached and for which algorithms
+    Show,
+    /// Remove entries for files that were deleted or changed
+    Prune,
+    /// Delete the cache file
+    Clear,
+}
+
+#[derive(Debug, Args)]
+pub struct RunArgs {
+    /// Directories to scan (defaults to the current directory)
+    #[arg(value_name = "ROOT")]
+    pub roots: Vec<String>,
+
+    /// Do not print the index once the run has finished
+    #[arg(short, long)]
+    pub quiet: bool,
+
+    /// Also write every group as JSON to FILE; `-` prints only the JSON to stdout
+    #[arg(long, value_name = "FILE")]
+    pub json: Option<PathBuf>,
+
+    /// Also write one CSV row per duplicate file to FILE (`-` for stdout)
+    #[arg(long, value_name = "FILE")]
+    pub csv: Option<PathBuf>,
+
+    /// Also write one CSV row per duplicate group to FILE (`-` for stdout)
+    #[arg(long, value_name = "FILE")]
+    pub csv_summary: Option<PathBuf>,
+
+    /// Also write a self-contained HTML report with sortable groups to FILE (`-` for stdout)
+    #[arg(long, value_name = "FILE")]
+    pub html: Option<PathBuf>,
+
+    /// Also write a Markdown report to FILE (`-` for stdout)
+    #[arg(long, value_name = "FILE")]
+    pub markdown: Option<PathBuf>,
+
+    /// Only consider files matching this glob (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    pub include: Vec<String>,
+
+    /// Skip files and directories matching this glob (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
+    /// Only consider files whose relative path or name matches this regex (repeatable)
+    #[arg(long, value_name = "REGEX")]
+    pub include_regex: Vec<String>,
+
+    /// Skip files and directories whose relative path or name matches this regex (repeatable)
+    #[arg(long, value_name = "REGEX")]
+    pub exclude_regex: Vec<String>,
+
+    /// Also skip paths ignored by .gitignore, .ignore and global git excludes (.dupignore applies unless --no-dupignore)
+    #[arg(long)]
+    pub respect_ignore: bool,
+
+    /// Scan paths listed in .dupignore files too
+    #[arg(long)]
+    pub no_dupignore: bool,
+
+    /// Follow symbolic links instead of skipping them (links that loop back are skipped)
+    #[arg(long)]
+    pub follow_symlinks: bool,
+
+    /// Ignore files smaller than this (K, M and G suffixes allowed); 0 includes empty files
+    #[arg(long, value_name = "SIZE", value_parser = parse_size, default_value = "1")]
+    pub min_size: u64,
+
+    /// Ignore files larger than this (K, M and G suffixes allowed)
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    pub max_size: Option<u64>,
+
+    /// Bytes hashed from the start (and end) of same-size files before a full hash; 0 disables
+    #[arg(long, value_name = "BYTES", default_value_t = DetectionOptions::default().partial_block_size)]
+    pub partial_block: u64,
+
+    /// Only hash the leading block in the partial stage
+    #[arg(long)]
+    pub no_tail: bool,
+
+    /// Compare every duplicate byte-by-byte before acting, splitting groups on mismatch
+    #[arg(long)]
+    pub verify: bool,
+
+    /// Reuse hashes of unchanged files from the cache in the scanned directory
+    #[arg(long)]
+    pub cache: bool,
+
+    /// Cache file to use instead of the one in the scanned directory (implies --cache)
+    #[arg(long, value_name = "FILE")]
+    pub cache_file: Option<PathBuf>,
+
+    /// Hash algorithm used to compare file contents
+    #[arg(long, value_enum, value_name = "ALGORITHM", default_value_t = HashAlgorithm::default())]
+    pub algorithm: HashAlgorithm,
+
+    /// Hashing threads; use 1 for spinning disks (defaults to the number of CPUs)
+    #[arg(short = 'j', long, value_name = "N")]
+    pub threads: Option<NonZeroUsize>,
+}
+
+#[derive(Debug, Args)]
+pub struct UndoArgs {
+    /// Directories that were previously organized (defaults to the current directory)
+    #[arg(value_name = "ROOT")]
+    pub roots: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct OrganizeArgs {
+    #[command(flatten)]
+    pub run: RunArgs,
+
+    /// Plan every move and write the index without creating folders or moving files
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// What to do with redundant copies; every action but `move` keeps one file per group
+    #[arg(long, value_enum, value_name = "ACTION", default_value_t = DuplicateAction::default())]
+    pub action: DuplicateAction,
+
+    /// Whether `--action symlink` writes relative or absolute link targets
+    #[arg(long, value_enum, value_name = "STYLE", default_value_t = SymlinkStyle::default())]
+    pub symlink_style: SymlinkStyle,
+
+    /// With `--action delete`, move files into this folder instead of the trash
+    #[arg(long, value_name = "DIR")]
+    pub trash_dir: Option<PathBuf>,
+
+    /// With `--action delete`, remove files for good instead of using the trash
+    #[arg(long, conflicts_with = "trash_dir")]
+    pub permanent: bool,
+
+    /// Delete without asking for confirmation first
+    #[arg(short, long)]
+    pub yes: bool,
+
+    /// Step through every group to choose the copy to keep and what to do with the rest
+    #[arg(short, long)]
+    pub interactive: bool,
+
+    /// Leave one file of each group in place, chosen by this rule
+    #[arg(long, value_enum, value_name = "RULE")]
+    pub keep: Option<KeepRule>,
+
+    /// Directory whose files are kept first (repeatable, implies `--keep priority`)
+    #[arg(long, value_name = "DIR")]
+    pub prefer: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum KeepRule {
+    /// The first file found while scanning
+    First,
+    /// The file with the oldest modification time
+    Oldest,
+    /// The file with the newest modification time
+    Newest,
+    /// The file with the shortest path
+    ShortestPath,
+    /// The file under the earliest `--prefer` directory
+    Priority,
+}
+
+impl OrganizeArgs {
+    pub fn delete_mode(&self) -> DeleteMode {
+        match (&self.trash_dir, self.permanent) {
+            (_, true) => DeleteMode::Permanent,
+            (Some(directory), false) => DeleteMode::Quarantine(directory.clone()),
+            (None, false) => DeleteMode::Trash,
+        }
+    }
+
+    pub fn keep_policy(&self) -> Option<KeepPolicy> {
+        let rule = match (self.keep, self.prefer.is_empty()) {
+            (Some(rule), _) => rule,
+            (None, false) => KeepRule::Priority,
+            (None, true) => return None,
+        };
+
+        Some(match rule {
+            KeepRule::First => KeepPolicy::FirstScanned,
+            KeepRule::Oldest => KeepPolicy::OldestModified,
+            KeepRule::Newest => KeepPolicy::NewestModified,
+            KeepRule::ShortestPath => KeepPolicy::ShortestPath,
+            KeepRule::Priority => KeepPolicy::Priority(self.prefer.clone()),
+        })
+    }
+}
+
+impl RunArgs {
+    pub fn root_paths(&self) -> Vec<String> {
+        root_paths_or_current(&self.roots)
+    }
+
+    pub fn scan_options(&self) -> ScanOptions {
+        ScanOptions {
+            include: self.include.clone(),
+            exclude: self.exclude.clone(),
+            include_regex: self.include_regex.clone(),
+            exclude_regex: self.exclude_regex.clone(),
+            respect_ignore_files: self.respect_ignore,
+            respect_dupignore: !self.no_dupignore,
+            follow_symlinks: self.follow_symlinks,
+            min_size: self.min_size,
+            max_size: self.max_size,
+        }
+    }
+
+    // A report written to stdout replaces the usual progress and summary output
+    pub fn report_to_stdout(&self) -> bool {
+        self.stdout_report_count() > 0
+    }
+
+    fn stdout_report_count(&self) -> usize {
+        [
+            &self.json,
+            &self.csv,
+            &self.csv_summary,
+            &self.html,
+            &self.markdown,
+        ]
+        .into_iter()
+        .filter(|destination| destination.as_deref() == Some(Path::new("-")))
+        .count()
+    }
+
+    pub fn detection_options(&self) -> DetectionOptions {
+        DetectionOptions {
+            partial_block_size: self.partial_block,
+            partial_tail: !self.no_tail,
+            algorithm: self.algorithm,
+            threads: self
+                .threads
+                .map_or_else(default_thread_count, NonZeroUsize::get),
+        }
+    }
+}
+
+impl CacheArgs {
+    pub fn cache_path(&self) -> PathBuf {
+        self.cache_file.clone().unwrap_or_else(|| {
+            HashCache::default_path(self.root.clone().unwrap_or_else(|| ".".to_string()))
+        })
+    }
+}
+
+impl UndoArgs {
+    pub fn root_paths(&self) -> Vec<String> {
+        root_paths_or_current(&self.roots)
+    }
+}
+
+fn root_paths_or_current(roots: &[String]) -> Vec<String> {
+    if roots.is_empty() {
+        vec![".".to_string()]
+    } else {
+        roots.to_vec()
+    }
+}
+
+// Accepts plain byte counts or a K, M or G suffix (powers of 1024, optional trailing `B`)
+fn parse_size(value: &str) -> Result<u64, String> {
+    let upper = value.trim().to_ascii_uppercase();
+    let digits = upper.strip_suffix('B').unwrap_or(&upper);
+    let (digits, multiplier) = match digits.char_indices().last() {
+        Some((index, 'K')) => (&digits[..index], 1024),
+        Some((index, 'M')) => (&digits[..index], 1024 * 1024),
+        Some((index, 'G')) => (&digits[..index], 1024 * 1024 * 1024),
+        _ => (digits, 1),
+    };
+
+    digits
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|count| count.checked_mul(multiplier))
+        .ok_or_else(|| format!("invalid size '{value}' (expected e.g. 512, 4K, 10M or 1G)"))
+}
@@ -0,0 +1,165 @@
+use crate::report::{FileReport, GroupReport, Renderer, Report};
+use std::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvRenderer {
+    // One row per file; `group_id` numbers the groups in report order so rows can be joined
+    // with the summary
+    Files,
+    // One row per group, for a summary sheet next to the per-file rows
+    Groups,
+}
+
+const FILE_COLUMNS: &[&str] = &[
+    "group_id",
+    "hash",
+    "size",
+    "allocated_bytes",
+    "links",
+    "path",
+    "modified_utc",
+    "is_keeper",
+    "planned_action",
+    "destination",
+];
+
+const SUMMARY_COLUMNS: &[&str] = &[
+    "group_id",
+    "hash",
+    "algorithm",
+    "size",
+    "file_count",
+    "redundant_bytes",
+    "logical_bytes",
+    "allocated_bytes",
+    "reclaimable_bytes",
+    "keeper",
+    "action",
+];
+
+impl Renderer for CsvRenderer {
+    fn render(&self, report: &Report) -> Result<String, io::Error> {
+        Ok(match self {
+            CsvRenderer::Files => render_files(report),
+            CsvRenderer::Groups => render_groups(report),
+        })
+    }
+}
+
+fn render_files(report: &Report) -> String {
+    let mut csv = String::new();
+    push_row(&mut csv, FILE_COLUMNS);
+
+    for (index, group) in report.groups.iter().enumerate() {
+        let group_id = (index + 1).to_string();
+        for file in &group.files {
+            let modified = file
+                .modified
+                .map(|modified| modified.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_default();
+            let destination = file
+                .destination
+                .as_ref()
+                .map(|destination| destination.to_string_lossy())
+                .unwrap_or_default();
+            push_row(
+                &mut csv,
+                &[
+                    group_id.as_str(),
+                    &group.hash,
+                    &file.size.to_string(),
+                    &file.allocated.to_string(),
+                    &file.links.to_string(),
+                    &file.path.to_string_lossy(),
+                    &modified,
+                    if file.keeper { "true" } else { "false" },
+                    planned_action(group, file),
+                    &destination,
+                ],
+            );
+        }
+    }
+
+    csv
+}
+
+fn render_groups(report: &Report) -> String {
+    let mut csv = String::new();
+    push_row(&mut csv, SUMMARY_COLUMNS);
+
+    for (index, group) in report.groups.iter().enumerate() {
+        let redundant_bytes = group.size * group.files.len().saturating_sub(1) as u64;
+        let keeper = group
+            .keeper
+            .as_ref()
+            .map(|keeper| keeper.to_string_lossy())
+            .unwrap_or_default();
+        let action = match group.action {
+            Some(action) => action.name(),
+            None if group.skipped_during_review => "skip",
+            None => "",
+        };
+        push_row(
+            &mut csv,
+            &[
+                (index + 1).to_string().as_str(),
+                &group.hash,
+                group.algorithm.name(),
+                &group.size.to_string(),
+                &group.files.len().to_string(),
+                &redundant_bytes.to_string(),
+                &group.logical_bytes.to_string(),
+                &group.allocated_bytes.to_string(),
+                &group.reclaimable_bytes.to_string(),
+                &keeper,
+                action,
+            ],
+        );
+    }
+
+    csv
+}
+
+fn planned_action(group: &GroupReport, file: &FileReport) -> &'static str {
+    if group.skipped_during_review {
+        return "skip";
+    }
+    match group.action {
+        _ if file.keeper => "keep",
+        _ if file.skipped.is_some() => "skipped",
+        Some(action) if file.destination.is_some() => action.name(),
+        _ => "",
+    }
+}
+
+// RFC 4180: fields are separated by commas and rows end in CRLF
+fn push_row(csv: &mut String, fields: &[&str]) {
+    for (index, field) in fields.iter().enumerate() {
+        if index > 0 {
+            csv.push(',');
+        }
+        push_field(csv, field);
+    }
+    csv.push_str("\r\n");
+}
+
+// File names may contain commas, quotes and even line breaks; such fields are quoted with any
+// embedded quotes doubled. Edge whitespace is quoted too so spreadsheets do not trim it.
+fn push_field(csv: &mut String, field: &str) {
+    // Spreadsheets run cells starting with these as formulas; a leading `'` keeps them text
+    let field = if field.starts_with(['=', '+', '-', '@']) {
+        format!("'{field}")
+    } else {
+        field.to_string()
+    };
+    let needs_quotes = field.contains([',', '"', '\n', '\r'])
+        || field.starts_with([' ', '\t', '\''])
+        || field.ends_with([' ', '\t']);
+    if needs_quotes {
+        csv.push('"');
+        csv.push_str(&field.replace('"', "\"\""));
+        csv.push('"');
+    } else {
+        csv.push_str(&field);
+    }
+}
@@ -1,11 +1,15 @@
 use crate::file_scanner::FileInfo;
-use crate::hasher::{Hash, compute_file_hash};
-use std::collections::HashMap;
+use crate::hash_cache::HashCache;
+use crate::hasher::{Hash, HashAlgorithm, compute_file_hash, compute_partial_hash};
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DuplicateGroup {
     pub files: Vec<PathBuf>,
+    pub algorithm: HashAlgorithm,
 }
 
 impl Default for DuplicateGroup {
@@ -16,7 +20,14 @@ impl Default for DuplicateGroup {
 
 impl DuplicateGroup {
     pub fn new() -> Self {
-        DuplicateGroup { files: Vec::new() }
+        Self::with_algorithm(HashAlgorithm::default())
+    }
+
+    pub fn with_algorithm(algorithm: HashAlgorithm) -> Self {
+        DuplicateGroup {
+            files: Vec::new(),
+            algorithm,
+        }
     }
 
     pub fn add_file(&mut self, file_path: PathBuf) {
@@ -32,27 +43,295 @@ impl DuplicateGroup {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct DetectionOptions {
+    // Size of the head (and tail) block hashed before the full hash; 0 skips the stage
+    pub partial_block_size: u64,
+    pub partial_tail: bool,
+    pub algorithm: HashAlgorithm,
+    // Worker threads used for hashing; 1 keeps all reads on the calling thread
+    pub threads: usize,
+}
+
+impl Default for DetectionOptions {
+    fn default() -> Self {
+        DetectionOptions {
+            partial_block_size: 4096,
+            partial_tail: true,
+            algorithm: HashAlgorithm::default(),
+            threads: default_thread_count(),
+        }
+    }
+}
+
+pub fn default_thread_count() -> usize {
+    thread::available_parallelism().map_or(1, |count| count.get())
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StageStats {
+    pub candidates: usize,
+    pub eliminated: usize,
+}
+
+impl StageStats {
+    fn new(candidates: usize, remaining: usize) -> Self {
+        StageStats {
+            candidates,
+            eliminated: candidates - remaining,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DetectionStats {
+    pub size: StageStats,
+    pub partial: StageStats,
+    pub full: StageStats,
+    // Full hashes taken from the hash cache instead of being computed
+    pub cache_hits: usize,
+    // Paths dropped because they are hardlinks (or followed symlinks) to an already seen file
+    pub linked_paths: usize,
+    // Followed symlinks skipped because they loop back to a parent directory; counted by the
+    // scanner, not the detector
+    pub link_loops: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct Detection {
+    pub groups: BTreeMap<Hash, DuplicateGroup>,
+    pub stats: DetectionStats,
+}
+
 pub struct DuplicateDetector;
 
 impl DuplicateDetector {
+    #[cfg(test)]
     pub fn find_duplicates(
         files: Vec<FileInfo>,
-    ) -> Result<HashMap<Hash, DuplicateGroup>, std::io::Error> {
-        let mut file_hashes: HashMap<Hash, DuplicateGroup> = HashMap::new();
+    ) -> Result<BTreeMap<Hash, DuplicateGroup>, std::io::Error> {
+        Self::find_duplicates_with_options(files, &DetectionOptions::default())
+            .map(|detection| detection.groups)
+    }
 
-        for file_info in files {
-            let hash = compute_file_hash(&file_info.path)?;
+    pub fn find_duplicates_with_options(
+        files: Vec<FileInfo>,
+        options: &DetectionOptions,
+    ) -> Result<Detection, std::io::Error> {
+        Self::detect(files, options, None)
+    }
+
+    // Full hashes are looked up in `cache` first, and newly computed ones are added to it
+    pub fn find_duplicates_with_cache(
+        files: Vec<FileInfo>,
+        options: &DetectionOptions,
+        cache: &mut HashCache,
+    ) -> Result<Detection, std::io::Error> {
+        Self::detect(files, options, Some(cache))
+    }
+
+    // Each stage only passes on files that still share a key with at least one other file:
+    // size, then a hash of the head/tail blocks, then the full content hash.
+    fn detect(
+        files: Vec<FileInfo>,
+        options: &DetectionOptions,
+        cache: Option<&mut HashCache>,
+    ) -> Result<Detection, std::io::Error> {
+        let mut stats = DetectionStats::default();
+
+        let (files, linked_paths) = collapse_links(files);
+        stats.linked_paths = linked_paths;
+
+        let scanned = files.len();
+        let mut candidates = Self::group_by_size(files);
+        stats.size = StageStats::new(scanned, count_files(&candidates));
+
+        if options.partial_block_size > 0 {
+            // Size groups whose full hashes are all cached gain nothing from a partial pass
+            let (cached, uncached): (Vec<_>, Vec<_>) = candidates.into_iter().partition(|group| {
+                cache.as_deref().is_some_and(|cache| {
+                    group
+                        .iter()
+                        .all(|file_info| cache.lookup(file_info, options.algorithm).is_some())
+                })
+            });
+
+            let before = count_files(&uncached);
+            let refined = Self::refine(uncached, options.threads, |file_info| {
+                compute_partial_hash(
+                    &file_info.path,
+                    options.partial_block_size,
+                    options.partial_tail,
+                    options.algorithm,
+                )
+            })?;
+            stats.partial = StageStats::new(before, count_files(&refined));
+            candidates = cached.into_iter().chain(refined).collect();
+        }
+
+        let before = count_files(&candidates);
+        let files: Vec<FileInfo> = candidates.into_iter().flatten().collect();
+        let cached_hashes: Vec<Option<Hash>> = files
+            .iter()
+            .map(|file_info| {
+                cache
+                    .as_deref()
+                    .and_then(|cache| cache.lookup(file_info, options.algorithm).cloned())
+            })
+            .collect();
+        stats.cache_hits = cached_hashes.iter().flatten().count();
+
+        let uncached: Vec<&FileInfo> = files
+            .iter()
+            .zip(&cached_hashes)
+            .filter(|(_, cached)| cached.is_none())
+            .map(|(file_info, _)| file_info)
+            .collect();
+        let computed = hash_files(&uncached, options.threads, |file_info| {
+            compute_file_hash(&file_info.path, options.algorithm)
+        })?;
+
+        if let Some(cache) = cache {
+            for (file_info, hash) in uncached.iter().zip(&computed) {
+                cache.insert(file_info, options.algorithm, hash.clone());
+            }
+        }
+
+        let mut computed = computed.into_iter();
+        let mut file_hashes: BTreeMap<Hash, DuplicateGroup> = BTreeMap::new();
+        for (file_info, cached) in files.into_iter().zip(cached_hashes) {
+            let Some(hash) = cached.or_else(|| computed.next()) else {
+                continue;
+            };
 
             file_hashes
                 .entry(hash)
-                .or_default()
+                .or_insert_with(|| DuplicateGroup::with_algorithm(options.algorithm))
                 .add_file(file_info.path);
         }
 
         // Keep only groups with multiple files (actual duplicates)
         // Single-file groups are filtered out but files remain untouched in their original locations
         file_hashes.retain(|_, group| !group.is_empty() && group.len() > 1);
+        stats.full = StageStats::new(before, file_hashes.values().map(DuplicateGroup::len).sum());
 
-        Ok(file_hashes)
+        Ok(Detection {
+            groups: file_hashes,
+            stats,
+        })
     }
+
+    fn group_by_size(files: Vec<FileInfo>) -> Vec<Vec<FileInfo>> {
+        let mut size_groups: BTreeMap<u64, Vec<FileInfo>> = BTreeMap::new();
+
+        for file_info in files {
+            size_groups
+                .entry(file_info.size)
+                .or_default()
+                .push(file_info);
+        }
+
+        size_groups
+            .into_values()
+            .filter(|group| group.len() > 1)
+            .collect()
+    }
+
+    // Splits every candidate group by `key`, dropping sub-groups left with a single file
+    fn refine<F>(
+        candidates: Vec<Vec<FileInfo>>,
+        threads: usize,
+        key: F,
+    ) -> Result<Vec<Vec<FileInfo>>, std::io::Error>
+    where
+        F: Fn(&FileInfo) -> Result<Hash, std::io::Error> + Sync,
+    {
+        let files: Vec<&FileInfo> = candidates.iter().flatten().collect();
+        let mut keys = hash_files(&files, threads, |file_info| key(file_info))?.into_iter();
+        let mut refined = Vec::new();
+
+        for group in candidates {
+            let mut sub_groups: BTreeMap<Hash, Vec<FileInfo>> = BTreeMap::new();
+            for (file_info, key) in group.into_iter().zip(keys.by_ref()) {
+                sub_groups.entry(key).or_default().push(file_info);
+            }
+            refined.extend(sub_groups.into_values().filter(|group| group.len() > 1));
+        }
+
+        Ok(refined)
+    }
+}
+
+// Keeps one path per physical file, so hardlinks never form a duplicate group. The first path
+// seen wins unless it was reached through a symlink and a real path to the same file turns up.
+fn collapse_links(files: Vec<FileInfo>) -> (Vec<FileInfo>, usize) {
+    let before = files.len();
+    let mut kept: Vec<FileInfo> = Vec::with_capacity(before);
+    let mut seen = HashMap::new();
+    for file_info in files {
+        let Some(id) = file_info.file_id() else {
+            kept.push(file_info);
+            continue;
+        };
+        match seen.get(&id) {
+            None => {
+                seen.insert(id, kept.len());
+                kept.push(file_info);
+            }
+            Some(&index) if kept[index].is_symlink && !file_info.is_symlink => {
+                kept[index] = file_info;
+            }
+            Some(_) => {}
+        }
+    }
+
+    let collapsed = before - kept.len();
+    (kept, collapsed)
+}
+
+fn count_files(groups: &[Vec<FileInfo>]) -> usize {
+    groups.iter().map(Vec::len).sum()
+}
+
+// Hashes `items` on up to `threads` workers. Results are returned in input order, and the
+// error reported is the one for the earliest failing item, so scheduling never shows through.
+fn hash_files<T, F>(items: &[T], threads: usize, hash: F) -> Result<Vec<Hash>, std::io::Error>
+where
+    T: Sync,
+    F: Fn(&T) -> Result<Hash, std::io::Error> + Sync,
+{
+    let threads = threads.clamp(1, items.len().max(1));
+    if threads == 1 {
+        return items.iter().map(hash).collect();
+    }
+
+    let next_item = AtomicUsize::new(0);
+    let mut results: Vec<(usize, Result<Hash, std::io::Error>)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut hashed = Vec::new();
+                    loop {
+                        let index = next_item.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else {
+                            break hashed;
+                        };
+                        hashed.push((index, hash(item)));
+                    }
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    });
+
+    results.sort_unstable_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
 }
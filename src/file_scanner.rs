@@ -1,44 +1,276 @@
+use crate::artifacts;
+use crate::ignore_rules::IgnoreRules;
+use crate::path_filter::PathFilter;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use walkdir::WalkDir;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Default)]
 pub struct FileInfo {
     pub path: PathBuf,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    pub inode: Option<u64>,
+    pub device: Option<u64>,
+    // Bytes the filesystem allocated, which differ from `size` for sparse files and partly
+    // filled blocks
+    pub allocated: u64,
+    // Hardlinks to the physical file, this path included
+    pub links: u64,
+    // Reached through a followed symlink; the metadata above is the target's
+    pub is_symlink: bool,
 }
 
 impl FileInfo {
     pub fn new(path: PathBuf) -> Result<Self, std::io::Error> {
-        let _metadata = fs::metadata(&path)?;
+        let metadata = fs::metadata(&path)?;
+        let is_symlink = is_symlink(&path);
 
-        Ok(FileInfo { path })
+        Ok(FileInfo {
+            path,
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+            inode: inode(&metadata),
+            device: device(&metadata),
+            allocated: allocated(&metadata),
+            links: links(&metadata),
+            is_symlink,
+        })
     }
+
+    // Paths with the same (device, inode) are hardlinks to one physical file
+    pub fn file_id(&self) -> Option<(u64, u64)> {
+        self.device.zip(self.inode)
+    }
+}
+
+// Links are never changed in place of their target: replacing one would break the user's link,
+// and linking to one would point at the link instead of the data
+pub fn is_symlink(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_symlink())
+}
+
+#[cfg(unix)]
+fn inode(metadata: &fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.ino())
+}
+
+#[cfg(not(unix))]
+fn inode(_metadata: &fs::Metadata) -> Option<u64> {
+    None
+}
+
+#[cfg(unix)]
+fn device(metadata: &fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.dev())
+}
+
+#[cfg(not(unix))]
+fn device(_metadata: &fs::Metadata) -> Option<u64> {
+    None
+}
+
+// `st_blocks` always counts 512-byte units, whatever the filesystem's block size
+#[cfg(unix)]
+fn allocated(metadata: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.blocks() * 512
+}
+
+#[cfg(not(unix))]
+fn allocated(metadata: &fs::Metadata) -> u64 {
+    metadata.len()
+}
+
+#[cfg(unix)]
+fn links(metadata: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.nlink()
+}
+
+#[cfg(not(unix))]
+fn links(_metadata: &fs::Metadata) -> u64 {
+    1
+}
+
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    // Gitignore-style globs; a pattern without `/` matches the file name at any depth
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub include_regex: Vec<String>,
+    pub exclude_regex: Vec<String>,
+    // Also honour .gitignore, .ignore and the global git excludes
+    pub respect_ignore_files: bool,
+    // Honour .dupignore files; on by default since they exist only for this tool
+    pub respect_dupignore: bool,
+    // Symlinks are skipped unless followed; followed links that loop back are skipped too
+    pub follow_symlinks: bool,
+    // Files outside these bounds are never reported; empty files are skipped by default
+    pub min_size: u64,
+    pub max_size: Option<u64>,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        ScanOptions {
+            include: Vec::new(),
+            exclude: Vec::new(),
+            include_regex: Vec::new(),
+            exclude_regex: Vec::new(),
+            respect_ignore_files: false,
+            respect_dupignore: true,
+            follow_symlinks: false,
+            min_size: 1,
+            max_size: None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Scan {
+    pub files: Vec<FileInfo>,
+    // Followed symlinks that lead back to one of their own ancestors
+    pub link_loops: usize,
 }
 
 pub struct FileScanner {
     pub root_path: PathBuf,
+    filter: PathFilter,
+    respect_ignore_files: bool,
+    respect_dupignore: bool,
+    follow_symlinks: bool,
+    min_size: u64,
+    max_size: Option<u64>,
+    skip_dirs: Vec<PathBuf>,
 }
 
 impl FileScanner {
     pub fn new<P: AsRef<Path>>(root_path: P) -> Self {
+        let defaults = ScanOptions::default();
         FileScanner {
             root_path: root_path.as_ref().to_path_buf(),
+            filter: PathFilter::default(),
+            respect_ignore_files: defaults.respect_ignore_files,
+            respect_dupignore: defaults.respect_dupignore,
+            follow_symlinks: defaults.follow_symlinks,
+            min_size: defaults.min_size,
+            max_size: defaults.max_size,
+            skip_dirs: Vec::new(),
         }
     }
 
+    pub fn with_options<P: AsRef<Path>>(
+        root_path: P,
+        options: &ScanOptions,
+    ) -> Result<Self, std::io::Error> {
+        let mut scanner = FileScanner::new(root_path);
+        scanner.filter = PathFilter::new(
+            &options.include,
+            &options.exclude,
+            &options.include_regex,
+            &options.exclude_regex,
+        )?;
+        scanner.respect_ignore_files = options.respect_ignore_files;
+        scanner.respect_dupignore = options.respect_dupignore;
+        scanner.follow_symlinks = options.follow_symlinks;
+        scanner.min_size = options.min_size;
+        scanner.max_size = options.max_size;
+        Ok(scanner)
+    }
+
+    // Other directories the tool writes into, such as a quarantine folder inside the root
+    pub fn with_skip_dirs<I>(mut self, skip_dirs: I) -> Self
+    where
+        I: IntoIterator<Item = PathBuf>,
+    {
+        let canonical_root = fs::canonicalize(&self.root_path).ok();
+        let skip_dirs = skip_dirs
+            .into_iter()
+            .filter_map(|dir| {
+                let canonical_dir = fs::canonicalize(dir).ok()?;
+                let relative_path = canonical_dir.strip_prefix(canonical_root.as_ref()?).ok()?;
+                (!relative_path.as_os_str().is_empty()).then(|| self.root_path.join(relative_path))
+            })
+            .collect();
+        self.skip_dirs = skip_dirs;
+        self
+    }
+
     pub fn scan_files(&self) -> Result<Vec<FileInfo>, std::io::Error> {
-        let mut files = Vec::new();
+        self.scan().map(|scan| scan.files)
+    }
+
+    pub fn scan(&self) -> Result<Scan, std::io::Error> {
+        let mut scan = Scan::default();
+        let mut ignore_rules = IgnoreRules::new(
+            &self.root_path,
+            self.respect_ignore_files,
+            self.respect_dupignore,
+        );
 
+        // Output of earlier runs is never rescanned, so repeated runs do not re-nest
+        // already organized files
         for entry in WalkDir::new(&self.root_path)
+            .follow_links(self.follow_symlinks)
             .into_iter()
-            .filter_map(|e| e.ok())
+            .filter_entry(|entry| {
+                !self.is_own_output(entry)
+                    && self.is_allowed(entry)
+                    && (entry.depth() == 0
+                        || !ignore_rules.is_ignored(entry.path(), entry.file_type().is_dir()))
+            })
         {
+            // When links are followed, walkdir reports a link back to one of its own ancestors
+            // as an error instead of descending into it; such loops are skipped but counted,
+            // while unreadable entries are skipped silently
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(error) => {
+                    if error.loop_ancestor().is_some() {
+                        scan.link_loops += 1;
+                    }
+                    continue;
+                }
+            };
+
+            // Without `follow_symlinks` a link's own file type is reported, so links are skipped
             if entry.file_type().is_file() {
                 let file_info = FileInfo::new(entry.path().to_path_buf())?;
-                files.push(file_info);
+                if self.is_within_size_bounds(file_info.size) {
+                    scan.files.push(file_info);
+                }
             }
         }
 
-        Ok(files)
+        Ok(scan)
+    }
+
+    fn is_within_size_bounds(&self, size: u64) -> bool {
+        size >= self.min_size && self.max_size.is_none_or(|max_size| size <= max_size)
+    }
+
+    fn is_allowed(&self, entry: &walkdir::DirEntry) -> bool {
+        match entry.path().strip_prefix(&self.root_path) {
+            Ok(relative_path) if entry.depth() > 0 => self
+                .filter
+                .allows(relative_path, entry.file_type().is_dir()),
+            _ => true,
+        }
+    }
+
+    fn is_own_output(&self, entry: &walkdir::DirEntry) -> bool {
+        if entry.file_type().is_dir() {
+            artifacts::is_duplicates_folder(entry.path(), &self.root_path)
+                || (entry.depth() > 0
+                    && (artifacts::is_quarantine_folder(entry.path())
+                        || artifacts::is_volume_trash(entry.path())))
+                || self.skip_dirs.iter().any(|dir| entry.path() == dir)
+        } else {
+            artifacts::is_artifact_file(entry.path())
+        }
     }
 }
@@ -0,0 +1,141 @@
+use crate::artifacts::CACHE_FILE_NAME;
+use crate::file_scanner::FileInfo;
+use crate::hasher::{Hash, HashAlgorithm};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    pub inode: Option<u64>,
+    pub algorithm: HashAlgorithm,
+    pub hash: Hash,
+}
+
+impl CacheEntry {
+    fn matches(&self, file_info: &FileInfo) -> bool {
+        self.size == file_info.size
+            && self.modified == file_info.modified
+            && self.inode == file_info.inode
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    entries: BTreeMap<PathBuf, CacheEntry>,
+}
+
+#[derive(Debug)]
+pub struct HashCache {
+    path: PathBuf,
+    cache: CacheFile,
+    dirty: bool,
+}
+
+impl HashCache {
+    pub fn default_path<P: AsRef<Path>>(root_path: P) -> PathBuf {
+        root_path.as_ref().join(CACHE_FILE_NAME)
+    }
+
+    // A cache that cannot be parsed is treated as empty; it only ever saves work
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
+        let path = path.as_ref().to_path_buf();
+        let cache = match fs::read(&path) {
+            Ok(content) => serde_json::from_slice(&content).unwrap_or_default(),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => CacheFile::default(),
+            Err(error) => return Err(error),
+        };
+
+        Ok(HashCache {
+            path,
+            cache,
+            dirty: false,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.entries.is_empty()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (&PathBuf, &CacheEntry)> {
+        self.cache.entries.iter()
+    }
+
+    // Entries only count when size, modification time and inode are all unchanged
+    pub fn lookup(&self, file_info: &FileInfo, algorithm: HashAlgorithm) -> Option<&Hash> {
+        let key = cache_key(&file_info.path)?;
+        self.cache
+            .entries
+            .get(&key)
+            .filter(|entry| entry.algorithm == algorithm && entry.matches(file_info))
+            .map(|entry| &entry.hash)
+    }
+
+    pub fn insert(&mut self, file_info: &FileInfo, algorithm: HashAlgorithm, hash: Hash) {
+        if let Some(key) = cache_key(&file_info.path) {
+            self.cache.entries.insert(
+                key,
+                CacheEntry {
+                    size: file_info.size,
+                    modified: file_info.modified,
+                    inode: file_info.inode,
+                    algorithm,
+                    hash,
+                },
+            );
+            self.dirty = true;
+        }
+    }
+
+    // Drops entries whose file is gone or whose metadata no longer matches
+    pub fn prune(&mut self) -> usize {
+        let before = self.cache.entries.len();
+        self.cache.entries.retain(|path, entry| {
+            FileInfo::new(path.clone()).is_ok_and(|file_info| entry.matches(&file_info))
+        });
+
+        let removed = before - self.cache.entries.len();
+        self.dirty |= removed > 0;
+        removed
+    }
+
+    pub fn save(&mut self) -> Result<(), io::Error> {
+        if !self.dirty {
+            return Ok(());
+        }
+
+        let content = serde_json::to_vec(&self.cache).map_err(io::Error::other)?;
+
+        // Write next to the cache and rename so an interrupted save never truncates it
+        let temp_path = self.path.with_extension("json.tmp");
+        fs::write(&temp_path, content)?;
+        fs::rename(&temp_path, &self.path)?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    pub fn clear<P: AsRef<Path>>(path: P) -> Result<bool, io::Error> {
+        match fs::remove_file(path) {
+            Ok(()) => Ok(true),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+fn cache_key(path: &Path) -> Option<PathBuf> {
+    std::path::absolute(path).ok()
+}
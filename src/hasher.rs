@@ -1,14 +1,90 @@
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::fmt;
 use std::fs::File;
-use std::io::Read;
+use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
 
 pub type Hash = String;
 
-pub fn compute_file_hash<P: AsRef<Path>>(file_path: P) -> Result<Hash, std::io::Error> {
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Blake3,
+    // 128-bit xxHash3; fast but not cryptographic
+    Xxh3,
+}
+
+impl HashAlgorithm {
+    pub fn name(self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Blake3 => "blake3",
+            HashAlgorithm::Xxh3 => "xxh3",
+        }
+    }
+
+    pub fn hasher(self) -> Box<dyn Hasher> {
+        match self {
+            HashAlgorithm::Sha256 => Box::new(Sha256::new()),
+            HashAlgorithm::Blake3 => Box::new(blake3::Hasher::new()),
+            HashAlgorithm::Xxh3 => Box::new(xxhash_rust::xxh3::Xxh3::new()),
+        }
+    }
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+pub trait Hasher {
+    fn update(&mut self, data: &[u8]);
+    fn finalize(self: Box<Self>) -> Hash;
+}
+
+impl Hasher for Sha256 {
+    fn update(&mut self, data: &[u8]) {
+        Digest::update(self, data);
+    }
+
+    fn finalize(self: Box<Self>) -> Hash {
+        hex::encode(Digest::finalize(*self))
+    }
+}
+
+impl Hasher for blake3::Hasher {
+    fn update(&mut self, data: &[u8]) {
+        blake3::Hasher::update(self, data);
+    }
+
+    fn finalize(self: Box<Self>) -> Hash {
+        blake3::Hasher::finalize(&self).to_hex().to_string()
+    }
+}
+
+impl Hasher for xxhash_rust::xxh3::Xxh3 {
+    fn update(&mut self, data: &[u8]) {
+        xxhash_rust::xxh3::Xxh3::update(self, data);
+    }
+
+    fn finalize(self: Box<Self>) -> Hash {
+        format!("{:032x}", self.digest128())
+    }
+}
+
+pub fn compute_file_hash<P: AsRef<Path>>(
+    file_path: P,
+    algorithm: HashAlgorithm,
+) -> Result<Hash, std::io::Error> {
     let mut file = File::open(file_path)?;
-    let mut hasher = Sha256::new();
-    let mut buffer = [0; 8192];
+    let mut hasher = algorithm.hasher();
+    let mut buffer = vec![0; 64 * 1024];
 
     loop {
         let bytes_read = file.read(&mut buffer)?;
@@ -18,6 +94,33 @@ pub fn compute_file_hash<P: AsRef<Path>>(file_path: P) -> Result<Hash, std::io::
         hasher.update(&buffer[..bytes_read]);
     }
 
-    let hash_result = hasher.finalize();
-    Ok(hex::encode(hash_result))
+    Ok(hasher.finalize())
+}
+
+// Hashes the first `block_size` bytes and, optionally, the last `block_size` bytes of a file.
+// Files that fit in the sampled range are hashed in full.
+pub fn compute_partial_hash<P: AsRef<Path>>(
+    file_path: P,
+    block_size: u64,
+    include_tail: bool,
+    algorithm: HashAlgorithm,
+) -> Result<Hash, std::io::Error> {
+    let mut file = File::open(file_path)?;
+    let file_len = file.metadata()?.len();
+    let mut hasher = algorithm.hasher();
+    let mut block = Vec::new();
+
+    (&mut file).take(block_size).read_to_end(&mut block)?;
+    hasher.update(&block);
+
+    if include_tail && file_len > block_size {
+        // Never re-read bytes that were already part of the head block
+        let tail_start = file_len.saturating_sub(block_size).max(block_size);
+        block.clear();
+        file.seek(SeekFrom::Start(tail_start))?;
+        file.take(block_size).read_to_end(&mut block)?;
+        hasher.update(&block);
+    }
+
+    Ok(hasher.finalize())
 }
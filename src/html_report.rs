@@ -0,0 +1,267 @@
+use crate::report::{Renderer, Report};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Directories listed in the breakdown; the rest are summed into one line
+const MAX_DIRECTORIES: usize = 50;
+
+const STYLE: &str = r#"
+body { font-family: system-ui, sans-serif; margin: 2rem; color: #222; }
+h1 { font-size: 1.5rem; }
+h2 { font-size: 1.2rem; margin-top: 2rem; }
+table.totals td { padding: 0.1rem 1rem 0.1rem 0; }
+.bar-row { display: grid; grid-template-columns: minmax(12rem, 40%) 1fr 7rem; gap: 0.5rem; align-items: center; margin: 0.15rem 0; }
+.bar-label { overflow: hidden; text-overflow: ellipsis; white-space: nowrap; font-family: monospace; }
+.bar { background: #eee; height: 0.9rem; }
+.bar span { display: block; height: 100%; background: #c0392b; }
+.bar-value { text-align: right; }
+details { border: 1px solid #ddd; border-radius: 4px; margin: 0.3rem 0; padding: 0.3rem 0.6rem; }
+summary { cursor: pointer; }
+summary .hash { font-family: monospace; color: #666; }
+table.files { border-collapse: collapse; margin: 0.5rem 0; width: 100%; }
+table.files th, table.files td { text-align: left; padding: 0.15rem 0.6rem; border-bottom: 1px solid #eee; }
+table.files td.path { font-family: monospace; word-break: break-all; }
+.keeper { font-weight: bold; }
+.controls { margin: 0.5rem 0; }
+"#;
+
+// Reorders the group list in place; the page stays usable without it
+const SCRIPT: &str = r#"
+function sortGroups(key) {
+  const list = document.getElementById('groups');
+  const groups = Array.from(list.children);
+  groups.sort((a, b) => key === 'id'
+    ? Number(a.dataset.id) - Number(b.dataset.id)
+    : Number(b.dataset[key]) - Number(a.dataset[key]));
+  groups.forEach(group => list.appendChild(group));
+}
+function expandAll(open) {
+  document.querySelectorAll('#groups details').forEach(group => group.open = open);
+}
+"#;
+
+// A single page with inline styles and script, so it can be archived next to the scan
+pub struct HtmlRenderer;
+
+impl Renderer for HtmlRenderer {
+    fn render(&self, report: &Report) -> Result<String, io::Error> {
+        Ok(render(report))
+    }
+}
+
+fn render(report: &Report) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>Duplicate files report</title>\n");
+    html.push_str(&format!(
+        "<style>{STYLE}</style>\n<script>{SCRIPT}</script>\n"
+    ));
+    html.push_str("</head>\n<body>\n<h1>Duplicate files report</h1>\n");
+
+    let run = &report.run;
+    let totals = &report.totals;
+    let roots: Vec<String> = run.roots.iter().map(|root| escape(root)).collect();
+    html.push_str("<table class=\"totals\">\n");
+    for (label, value) in [
+        ("Scanned", roots.join(", ")),
+        (
+            "Generated",
+            run.generated_at.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+        ),
+        ("Mode", run.mode.name().to_string()),
+        ("Files scanned", totals.files_scanned.to_string()),
+        ("Duplicate groups", totals.duplicate_groups.to_string()),
+        ("Duplicate files", totals.duplicate_files.to_string()),
+        (
+            "Duplicate data",
+            format!(
+                "{} ({} allocated on disk)",
+                format_bytes(totals.logical_bytes),
+                format_bytes(totals.allocated_bytes)
+            ),
+        ),
+        (
+            "Wasted space",
+            format!(
+                "{} reclaimable on disk from {} redundant copies ({} logical)",
+                format_bytes(totals.reclaimable_bytes),
+                totals.redundant_files,
+                format_bytes(totals.redundant_bytes)
+            ),
+        ),
+    ] {
+        html.push_str(&format!("<tr><td>{label}</td><td>{value}</td></tr>\n"));
+    }
+    if let Some(action) = run.action {
+        let dry_run = if run.dry_run { " (dry run)" } else { "" };
+        html.push_str(&format!(
+            "<tr><td>Action</td><td>{action}{dry_run}: {} files, {}</td></tr>\n",
+            totals.files_acted_on,
+            format_bytes(totals.bytes_acted_on)
+        ));
+    }
+    html.push_str("</table>\n");
+
+    render_directories(&mut html, report);
+    render_groups(&mut html, report);
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+// Where the reclaimable copies live, by their parent directory
+fn render_directories(html: &mut String, report: &Report) {
+    let mut wasted: HashMap<PathBuf, (u64, usize)> = HashMap::new();
+    for group in &report.groups {
+        for file in group.redundant_files().filter(|file| file.links <= 1) {
+            let directory = file.path.parent().unwrap_or(Path::new("")).to_path_buf();
+            let entry = wasted.entry(directory).or_default();
+            entry.0 += file.allocated;
+            entry.1 += 1;
+        }
+    }
+    if wasted.is_empty() {
+        return;
+    }
+
+    let mut directories: Vec<(PathBuf, (u64, usize))> = wasted.into_iter().collect();
+    directories.sort_by(|left, right| right.1.0.cmp(&left.1.0).then(left.0.cmp(&right.0)));
+    let largest = directories[0].1.0.max(1);
+
+    html.push_str("<h2>Wasted space by directory</h2>\n");
+    for (directory, (bytes, copies)) in directories.iter().take(MAX_DIRECTORIES) {
+        let name = if directory.as_os_str().is_empty() {
+            ".".to_string()
+        } else {
+            escape(&directory.to_string_lossy())
+        };
+        html.push_str(&bar_row(
+            &name,
+            *bytes,
+            largest,
+            &format!("{} ({copies})", format_bytes(*bytes)),
+        ));
+    }
+    if directories.len() > MAX_DIRECTORIES {
+        let rest = &directories[MAX_DIRECTORIES..];
+        let bytes: u64 = rest.iter().map(|(_, (bytes, _))| bytes).sum();
+        html.push_str(&format!(
+            "<p>{} more directories hold another {}.</p>\n",
+            rest.len(),
+            format_bytes(bytes)
+        ));
+    }
+}
+
+fn render_groups(html: &mut String, report: &Report) {
+    if report.groups.is_empty() {
+        html.push_str("<p>No duplicate files found.</p>\n");
+        return;
+    }
+
+    html.push_str("<h2>Duplicate groups</h2>\n<div class=\"controls\">\n");
+    html.push_str("Sort by <select onchange=\"sortGroups(this.value)\">");
+    html.push_str("<option value=\"id\">scan order</option>");
+    html.push_str("<option value=\"wasted\">wasted bytes</option>");
+    html.push_str("<option value=\"count\">number of copies</option>");
+    html.push_str("<option value=\"size\">file size</option></select>\n");
+    html.push_str("<button onclick=\"expandAll(true)\">Expand all</button>\n");
+    html.push_str("<button onclick=\"expandAll(false)\">Collapse all</button>\n</div>\n");
+    html.push_str("<div id=\"groups\">\n");
+
+    for (index, group) in report.groups.iter().enumerate() {
+        let wasted = group.reclaimable_bytes;
+        html.push_str(&format!(
+            "<details data-id=\"{}\" data-wasted=\"{wasted}\" data-count=\"{}\" data-size=\"{}\">\n",
+            index + 1,
+            group.files.len(),
+            group.size
+        ));
+        html.push_str(&format!(
+            "<summary>Group {}: {} copies of {}, {} wasted <span class=\"hash\">{} {}</span></summary>\n",
+            index + 1,
+            group.files.len(),
+            format_bytes(group.size),
+            format_bytes(wasted),
+            group.algorithm,
+            escape(&group.hash)
+        ));
+
+        // Scans and reports leave every file alone, so there is no outcome to show
+        let show_outcome = group.action.is_some() || group.skipped_during_review;
+        html.push_str("<table class=\"files\">\n<tr><th>Path</th><th>Modified (UTC)</th>");
+        if show_outcome {
+            html.push_str("<th>Outcome</th>");
+        }
+        html.push_str("</tr>\n");
+        for file in &group.files {
+            let class = if file.keeper { " keeper" } else { "" };
+            let modified = file
+                .modified
+                .map(|modified| modified.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_default();
+            html.push_str(&format!(
+                "<tr><td class=\"path{class}\">{}</td><td>{modified}</td>",
+                escape(&file.path.to_string_lossy())
+            ));
+            if show_outcome {
+                let outcome = if group.skipped_during_review {
+                    "skipped during review".to_string()
+                } else if file.keeper {
+                    "kept".to_string()
+                } else if let Some(reason) = &file.skipped {
+                    format!("skipped: {}", escape(reason))
+                } else if let Some(destination) = &file.destination {
+                    let action = group.action.map(|action| action.name()).unwrap_or_default();
+                    format!("{action} &rarr; {}", escape(&destination.to_string_lossy()))
+                } else {
+                    String::new()
+                };
+                html.push_str(&format!("<td>{outcome}</td>"));
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>\n</details>\n");
+    }
+
+    html.push_str("</div>\n");
+}
+
+fn bar_row(label: &str, value: u64, largest: u64, text: &str) -> String {
+    let width = value as f64 / largest as f64 * 100.0;
+    format!(
+        "<div class=\"bar-row\"><div class=\"bar-label\" title=\"{label}\">{label}</div>\
+         <div class=\"bar\"><span style=\"width: {width:.1}%\"></span></div>\
+         <div class=\"bar-value\">{text}</div></div>\n"
+    )
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
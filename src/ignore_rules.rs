@@ -0,0 +1,86 @@
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+pub const DUPIGNORE_FILE_NAME: &str = ".dupignore";
+
+// Files read from every directory, lowest precedence first
+const VCS_IGNORE_FILE_NAMES: &[&str] = &[".gitignore", ".ignore"];
+
+// Resolves ignore files the way git does: rules in deeper directories win over rules higher
+// up, and within one directory later files (and later lines) win over earlier ones.
+#[derive(Debug)]
+pub struct IgnoreRules {
+    root_path: PathBuf,
+    file_names: Vec<&'static str>,
+    global: Option<Gitignore>,
+    per_directory: HashMap<PathBuf, Option<Gitignore>>,
+}
+
+impl IgnoreRules {
+    // `.gitignore`, `.ignore` and the global git excludes apply with `respect_vcs_ignores`,
+    // `.dupignore` with `respect_dupignore`
+    pub fn new<P: AsRef<Path>>(
+        root_path: P,
+        respect_vcs_ignores: bool,
+        respect_dupignore: bool,
+    ) -> Self {
+        let mut file_names = Vec::new();
+        if respect_vcs_ignores {
+            file_names.extend_from_slice(VCS_IGNORE_FILE_NAMES);
+        }
+        if respect_dupignore {
+            file_names.push(DUPIGNORE_FILE_NAME);
+        }
+
+        let global = respect_vcs_ignores
+            .then(|| Gitignore::global().0)
+            .filter(|global| !global.is_empty());
+
+        IgnoreRules {
+            root_path: root_path.as_ref().to_path_buf(),
+            file_names,
+            global,
+            per_directory: HashMap::new(),
+        }
+    }
+
+    pub fn is_ignored(&mut self, path: &Path, is_dir: bool) -> bool {
+        for directory in path.ancestors().skip(1) {
+            if let Some(rules) = self.rules_for(directory) {
+                match rules.matched(path, is_dir) {
+                    Match::Ignore(_) => return true,
+                    Match::Whitelist(_) => return false,
+                    Match::None => {}
+                }
+            }
+
+            if directory == self.root_path {
+                break;
+            }
+        }
+
+        self.global
+            .as_ref()
+            .is_some_and(|global| global.matched(path, is_dir).is_ignore())
+    }
+
+    fn rules_for(&mut self, directory: &Path) -> Option<&Gitignore> {
+        let file_names = &self.file_names;
+        self.per_directory
+            .entry(directory.to_path_buf())
+            .or_insert_with(|| {
+                let mut builder = GitignoreBuilder::new(directory);
+                for file_name in file_names {
+                    let path = directory.join(file_name);
+                    if path.is_file() {
+                        // Malformed lines are skipped; the rest of the file still applies
+                        let _ = builder.add(path);
+                    }
+                }
+                builder.build().ok().filter(|rules| !rules.is_empty())
+            })
+            .as_ref()
+    }
+}
@@ -0,0 +1,240 @@
+use crate::artifacts::{DUPLICATES_FOLDER_NAME, JOURNAL_FILE_NAME};
+use crate::hasher::{Hash, HashAlgorithm, compute_file_hash};
+use crate::linker;
+use crate::organizer::DuplicateAction;
+use crate::trash::{self, Trash};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub original_path: PathBuf,
+    // Where a moved file went, or the kept file a link points at
+    pub new_path: PathBuf,
+    pub hash: Hash,
+    // Entries written before the algorithm was recorded were always SHA-256
+    #[serde(default)]
+    pub algorithm: HashAlgorithm,
+    // Entries written before other actions existed were always moves
+    #[serde(default)]
+    pub action: DuplicateAction,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+pub struct JournalWriter {
+    file: File,
+}
+
+impl JournalWriter {
+    pub fn open<P: AsRef<Path>>(root_path: P) -> Result<Self, io::Error> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(journal_path(root_path))?;
+
+        Ok(JournalWriter { file })
+    }
+
+    // Each entry is flushed on its own so an interrupted run still leaves a usable journal
+    pub fn record(
+        &mut self,
+        original_path: &Path,
+        new_path: &Path,
+        hash: &Hash,
+        algorithm: HashAlgorithm,
+        action: DuplicateAction,
+    ) -> io::Result<()> {
+        let entry = JournalEntry {
+            original_path: std::path::absolute(original_path)?,
+            new_path: std::path::absolute(new_path)?,
+            hash: hash.clone(),
+            algorithm,
+            action,
+            timestamp: Utc::now(),
+        };
+
+        let mut line = serde_json::to_string(&entry).map_err(io::Error::other)?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes())?;
+        self.file.flush()
+    }
+}
+
+#[derive(Debug)]
+pub enum ConflictReason {
+    MissingFile,
+    OriginalOccupied,
+    // The linked path was replaced by something else since the run
+    NoLongerLinked,
+    HashMismatch { actual: Hash },
+    Io(io::Error),
+}
+
+impl fmt::Display for ConflictReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConflictReason::MissingFile => write!(f, "moved file no longer exists"),
+            ConflictReason::OriginalOccupied => write!(f, "original path is occupied"),
+            ConflictReason::NoLongerLinked => {
+                write!(f, "original path no longer links to the kept file")
+            }
+            ConflictReason::HashMismatch { actual } => {
+                write!(f, "file content changed (hash is now {actual})")
+            }
+            ConflictReason::Io(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RestoreConflict {
+    pub entry: JournalEntry,
+    pub reason: ConflictReason,
+}
+
+#[derive(Debug, Default)]
+pub struct RestoreSummary {
+    pub restored: Vec<JournalEntry>,
+    pub conflicts: Vec<RestoreConflict>,
+}
+
+pub fn journal_path<P: AsRef<Path>>(root_path: P) -> PathBuf {
+    root_path.as_ref().join(JOURNAL_FILE_NAME)
+}
+
+pub fn read_journal<P: AsRef<Path>>(root_path: P) -> Result<Vec<JournalEntry>, io::Error> {
+    let file = match File::open(journal_path(root_path)) {
+        Ok(file) => file,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = serde_json::from_str(&line)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+// Replays the journal backwards; entries that could not be restored stay in the journal
+pub fn restore<P: AsRef<Path>>(root_path: P) -> Result<RestoreSummary, io::Error> {
+    let root_path = root_path.as_ref();
+    let entries = read_journal(root_path)?;
+    let mut summary = RestoreSummary::default();
+
+    for entry in entries.into_iter().rev() {
+        match restore_entry(&entry) {
+            Ok(()) => summary.restored.push(entry),
+            Err(reason) => summary.conflicts.push(RestoreConflict { entry, reason }),
+        }
+    }
+
+    let remaining: Vec<&JournalEntry> = summary.conflicts.iter().rev().map(|c| &c.entry).collect();
+    rewrite_journal(root_path, &remaining)?;
+    Ok(summary)
+}
+
+fn restore_entry(entry: &JournalEntry) -> Result<(), ConflictReason> {
+    match entry.action {
+        DuplicateAction::Move => restore_move(entry),
+        DuplicateAction::Delete => restore_deleted(entry),
+        DuplicateAction::Hardlink | DuplicateAction::Symlink => restore_link(entry),
+        // Never journaled: files sharing extents are already independent copies
+        DuplicateAction::Reflink => Ok(()),
+    }
+}
+
+fn restore_move(entry: &JournalEntry) -> Result<(), ConflictReason> {
+    move_back(entry)?;
+
+    // Tidy up the group folder once its last file has gone back; failure just leaves it behind
+    if let Some(folder) = entry.new_path.parent() {
+        let _ = fs::remove_dir(folder);
+        if let Some(duplicates_folder) = folder.parent()
+            && duplicates_folder.file_name() == Some(DUPLICATES_FOLDER_NAME.as_ref())
+        {
+            let _ = fs::remove_dir(duplicates_folder);
+        }
+    }
+
+    Ok(())
+}
+
+// Only deletions into the trash or a quarantine folder are journaled
+fn restore_deleted(entry: &JournalEntry) -> Result<(), ConflictReason> {
+    move_back(entry)?;
+    Trash::forget(&entry.new_path);
+    Ok(())
+}
+
+fn move_back(entry: &JournalEntry) -> Result<(), ConflictReason> {
+    if !entry.new_path.exists() {
+        return Err(ConflictReason::MissingFile);
+    }
+    if entry.original_path.exists() {
+        return Err(ConflictReason::OriginalOccupied);
+    }
+
+    let actual = compute_file_hash(&entry.new_path, entry.algorithm).map_err(ConflictReason::Io)?;
+    if actual != entry.hash {
+        return Err(ConflictReason::HashMismatch { actual });
+    }
+
+    if let Some(parent) = entry.original_path.parent() {
+        fs::create_dir_all(parent).map_err(ConflictReason::Io)?;
+    }
+    trash::move_file(&entry.new_path, &entry.original_path).map_err(ConflictReason::Io)
+}
+
+// Gives the original path its own copy of the kept file again
+fn restore_link(entry: &JournalEntry) -> Result<(), ConflictReason> {
+    if !entry.new_path.exists() || !entry.original_path.exists() {
+        return Err(ConflictReason::MissingFile);
+    }
+    let is_symlink = fs::symlink_metadata(&entry.original_path)
+        .map_err(ConflictReason::Io)?
+        .file_type()
+        .is_symlink();
+    if is_symlink != (entry.action == DuplicateAction::Symlink)
+        || !linker::same_file(&entry.new_path, &entry.original_path).map_err(ConflictReason::Io)?
+    {
+        return Err(ConflictReason::NoLongerLinked);
+    }
+
+    let actual = compute_file_hash(&entry.new_path, entry.algorithm).map_err(ConflictReason::Io)?;
+    if actual != entry.hash {
+        return Err(ConflictReason::HashMismatch { actual });
+    }
+
+    linker::replace_with_copy(&entry.new_path, &entry.original_path).map_err(ConflictReason::Io)
+}
+
+fn rewrite_journal(root_path: &Path, entries: &[&JournalEntry]) -> Result<(), io::Error> {
+    let path = journal_path(root_path);
+    let mut content = String::new();
+    for entry in entries {
+        content.push_str(&serde_json::to_string(entry).map_err(io::Error::other)?);
+        content.push('\n');
+    }
+
+    if content.is_empty() {
+        match fs::remove_file(&path) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+            _ => Ok(()),
+        }
+    } else {
+        fs::write(path, content)
+    }
+}
@@ -0,0 +1,12 @@
+use crate::report::{Renderer, Report};
+use std::io;
+
+pub struct JsonRenderer;
+
+impl Renderer for JsonRenderer {
+    fn render(&self, report: &Report) -> Result<String, io::Error> {
+        let mut json = serde_json::to_string_pretty(report).map_err(io::Error::other)?;
+        json.push('\n');
+        Ok(json)
+    }
+}
@@ -0,0 +1,63 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeepPolicy {
+    FirstScanned,
+    OldestModified,
+    NewestModified,
+    ShortestPath,
+    // Keep the file under the earliest matching directory; falls back to scan order
+    Priority(Vec<PathBuf>),
+}
+
+impl KeepPolicy {
+    pub fn select<'a>(&self, files: &'a [PathBuf]) -> Result<Option<&'a PathBuf>, std::io::Error> {
+        if files.is_empty() {
+            return Ok(None);
+        }
+
+        let keeper = match self {
+            KeepPolicy::FirstScanned => &files[0],
+            KeepPolicy::OldestModified => {
+                let modified = modification_times(files)?;
+                min_by_key(files, |i| modified[i])
+            }
+            KeepPolicy::NewestModified => {
+                let modified = modification_times(files)?;
+                min_by_key(files, |i| std::cmp::Reverse(modified[i]))
+            }
+            KeepPolicy::ShortestPath => min_by_key(files, |i| files[i].as_os_str().len()),
+            KeepPolicy::Priority(preferred) => {
+                let preferred: Vec<PathBuf> = preferred.iter().map(|p| canonical(p)).collect();
+                let canonical_files: Vec<PathBuf> = files.iter().map(|f| canonical(f)).collect();
+                min_by_key(files, |i| {
+                    preferred
+                        .iter()
+                        .position(|prefix| canonical_files[i].starts_with(prefix))
+                        .unwrap_or(preferred.len())
+                })
+            }
+        };
+
+        Ok(Some(keeper))
+    }
+}
+
+// `Iterator::min_by_key` keeps the first minimum, so ties resolve to scan order
+fn min_by_key<K: Ord>(files: &[PathBuf], key: impl Fn(usize) -> K) -> &PathBuf {
+    let index = (0..files.len()).min_by_key(|&i| key(i)).unwrap_or(0);
+    &files[index]
+}
+
+fn modification_times(files: &[PathBuf]) -> Result<Vec<SystemTime>, std::io::Error> {
+    files
+        .iter()
+        .map(|path| fs::metadata(path)?.modified())
+        .collect()
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
@@ -0,0 +1,24 @@
+pub mod cli;
+pub mod csv_report;
+pub mod duplicate_detector;
+pub mod file_scanner;
+pub mod hash_cache;
+pub mod hasher;
+pub mod html_report;
+pub mod journal;
+pub mod json_report;
+pub mod keeper;
+pub mod markdown_report;
+pub mod organizer;
+pub mod report;
+pub mod text_report;
+pub mod verifier;
+pub mod workflow;
+
+mod artifacts;
+mod ignore_rules;
+mod linker;
+mod path_filter;
+#[cfg(test)]
+mod tests;
+mod trash;
@@ -0,0 +1,278 @@
+use crate::file_scanner::FileInfo;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Replaces `path` with a hardlink to `target`. The link is created under a temporary name next
+// to `path` and renamed over it, so `path` never goes missing, even if the run is interrupted.
+pub fn replace_with_hardlink(target: &Path, path: &Path) -> Result<(), io::Error> {
+    // `hard_link` does not follow symlinks, so `path` would become a second name for the link
+    // itself, which dangles wherever the link is relative
+    if fs::symlink_metadata(target)?.file_type().is_symlink() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is a symbolic link", target.display()),
+        ));
+    }
+    if !same_filesystem(target, path)? {
+        return Err(io::Error::new(
+            io::ErrorKind::CrossesDevices,
+            format!(
+                "{} and {} are on different filesystems",
+                target.display(),
+                path.display()
+            ),
+        ));
+    }
+
+    let temp_path = temporary_path(path);
+    fs::hard_link(target, &temp_path)?;
+    rename_into_place(&temp_path, path)
+}
+
+// Replaces `path` with a symlink to `target`, either relative to the link's directory or
+// absolute. Like hardlinks, the link is created under a temporary name and renamed over `path`.
+pub fn replace_with_symlink(target: &Path, path: &Path, relative: bool) -> Result<(), io::Error> {
+    let target = fs::canonicalize(target)?;
+    let link_target = if relative {
+        let directory = path
+            .parent()
+            .filter(|directory| !directory.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        let directory = fs::canonicalize(directory)?;
+        relative_path(&directory, &target)
+    } else {
+        target
+    };
+
+    let temp_path = temporary_path(path);
+    symlink_file(&link_target, &temp_path)?;
+    rename_into_place(&temp_path, path)
+}
+
+// Asks the filesystem to share `source`'s extents with `destination` (Btrfs, XFS and other
+// copy-on-write filesystems). Both paths stay independent files; the kernel compares the bytes
+// itself and refuses if they differ, so this can never change what either file contains.
+#[cfg(target_os = "linux")]
+pub fn share_extents(source: &Path, destination: &Path) -> Result<(), io::Error> {
+    use std::fs::{File, OpenOptions};
+    use std::os::fd::AsRawFd;
+
+    let source_file = File::open(source)?;
+    let destination_file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(destination)?;
+    let length = source_file.metadata()?.len();
+    if destination_file.metadata()?.len() != length {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "file sizes differ",
+        ));
+    }
+
+    let mut offset = 0;
+    while offset < length {
+        let mut range = dedupe::FileDedupeRange {
+            src_offset: offset,
+            src_length: (length - offset).min(dedupe::MAX_CHUNK),
+            dest_count: 1,
+            reserved1: 0,
+            reserved2: 0,
+            info: [dedupe::FileDedupeRangeInfo {
+                dest_fd: i64::from(destination_file.as_raw_fd()),
+                dest_offset: offset,
+                bytes_deduped: 0,
+                status: 0,
+                reserved: 0,
+            }],
+        };
+
+        // SAFETY: `range` is a fully initialised `file_dedupe_range` followed by exactly the
+        // one `file_dedupe_range_info` its `dest_count` declares, it outlives the call, and
+        // both descriptors stay open until the files are dropped after the loop.
+        let result = unsafe {
+            libc::ioctl(
+                source_file.as_raw_fd(),
+                dedupe::FIDEDUPERANGE as _,
+                &mut range,
+            )
+        };
+        if result < 0 {
+            return Err(dedupe::describe_error(io::Error::last_os_error()));
+        }
+
+        let info = &range.info[0];
+        match info.status {
+            dedupe::FILE_DEDUPE_RANGE_SAME if info.bytes_deduped > 0 => {
+                offset += info.bytes_deduped
+            }
+            // Some filesystems leave an unaligned tail alone; what was shared stays shared
+            dedupe::FILE_DEDUPE_RANGE_SAME if offset > 0 => break,
+            dedupe::FILE_DEDUPE_RANGE_SAME => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "filesystem shared no extents",
+                ));
+            }
+            dedupe::FILE_DEDUPE_RANGE_DIFFERS => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "file contents differ",
+                ));
+            }
+            status => {
+                return Err(dedupe::describe_error(io::Error::from_raw_os_error(
+                    -status,
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn share_extents(_source: &Path, _destination: &Path) -> Result<(), io::Error> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "extent sharing is only available on Linux",
+    ))
+}
+
+// Mirrors `struct file_dedupe_range` from <linux/fs.h>, which libc does not expose
+#[cfg(target_os = "linux")]
+mod dedupe {
+    use std::io;
+
+    // _IOWR(0x94, 54, struct file_dedupe_range) in the asm-generic ioctl encoding. Architectures
+    // with a different encoding get ENOTTY back, which is reported as unsupported.
+    pub const FIDEDUPERANGE: u32 = 0xC018_9436;
+    pub const FILE_DEDUPE_RANGE_SAME: i32 = 0;
+    pub const FILE_DEDUPE_RANGE_DIFFERS: i32 = 1;
+    // Btrfs dedupes at most 16 MiB per call, so larger files are shared in chunks
+    pub const MAX_CHUNK: u64 = 16 * 1024 * 1024;
+
+    #[repr(C)]
+    pub struct FileDedupeRangeInfo {
+        pub dest_fd: i64,
+        pub dest_offset: u64,
+        pub bytes_deduped: u64,
+        pub status: i32,
+        pub reserved: u32,
+    }
+
+    #[repr(C)]
+    pub struct FileDedupeRange {
+        pub src_offset: u64,
+        pub src_length: u64,
+        pub dest_count: u16,
+        pub reserved1: u16,
+        pub reserved2: u32,
+        pub info: [FileDedupeRangeInfo; 1],
+    }
+
+    pub fn describe_error(error: io::Error) -> io::Error {
+        match error.raw_os_error() {
+            Some(libc::EOPNOTSUPP | libc::ENOTTY | libc::EINVAL | libc::ENOSYS) => io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("filesystem does not support extent sharing ({error})"),
+            ),
+            Some(libc::EXDEV) => io::Error::new(
+                io::ErrorKind::CrossesDevices,
+                "files are on different filesystems",
+            ),
+            _ => error,
+        }
+    }
+}
+
+// Replaces `path` with an independent copy of `source`, breaking any link between them
+pub fn replace_with_copy(source: &Path, path: &Path) -> Result<(), io::Error> {
+    let temp_path = temporary_path(path);
+    fs::copy(source, &temp_path)?;
+    rename_into_place(&temp_path, path)
+}
+
+// Platforms without device numbers report every pair as the same filesystem; creating the
+// link itself still fails there if the paths are on different volumes
+pub fn same_filesystem(left: &Path, right: &Path) -> Result<bool, io::Error> {
+    let left = FileInfo::new(left.to_path_buf())?;
+    let right = FileInfo::new(right.to_path_buf())?;
+    Ok(match (left.device, right.device) {
+        (Some(left), Some(right)) => left == right,
+        _ => true,
+    })
+}
+
+// Without inode numbers there is nothing to compare, so the paths are assumed to be linked
+pub fn same_file(left: &Path, right: &Path) -> Result<bool, io::Error> {
+    let left = FileInfo::new(left.to_path_buf())?;
+    let right = FileInfo::new(right.to_path_buf())?;
+    Ok(match (left.file_id(), right.file_id()) {
+        (Some(left), Some(right)) => left == right,
+        _ => true,
+    })
+}
+
+// Both paths must be absolute and free of `.` and `..` components
+fn relative_path(from_directory: &Path, to: &Path) -> PathBuf {
+    let from: Vec<_> = from_directory.components().collect();
+    let to_components: Vec<_> = to.components().collect();
+    let common = from
+        .iter()
+        .zip(&to_components)
+        .take_while(|(left, right)| left == right)
+        .count();
+
+    let mut relative = PathBuf::new();
+    for _ in common..from.len() {
+        relative.push("..");
+    }
+    for component in &to_components[common..] {
+        relative.push(component);
+    }
+    relative
+}
+
+#[cfg(unix)]
+fn symlink_file(target: &Path, link: &Path) -> Result<(), io::Error> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink_file(target: &Path, link: &Path) -> Result<(), io::Error> {
+    std::os::windows::fs::symlink_file(target, link)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn symlink_file(_target: &Path, _link: &Path) -> Result<(), io::Error> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "symbolic links are not supported on this platform",
+    ))
+}
+
+fn rename_into_place(temp_path: &Path, path: &Path) -> Result<(), io::Error> {
+    fs::rename(temp_path, path).inspect_err(|_| {
+        let _ = fs::remove_file(temp_path);
+    })
+}
+
+fn temporary_path(path: &Path) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+
+    let mut index = 0;
+    loop {
+        let candidate = directory.join(format!(".{file_name}.dedupe-{index}.tmp"));
+        // `exists` follows links, so a dangling temporary symlink would look free
+        if fs::symlink_metadata(&candidate).is_err() {
+            break candidate;
+        }
+        index += 1;
+    }
+}
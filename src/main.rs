@@ -1,18 +1,47 @@
-mod duplicate_detector;
-mod file_scanner;
-mod hasher;
-mod organizer;
+mod review;
+// The library's tests live in src/tests/mod.rs; these cover the binary's own helpers
 #[cfg(test)]
+#[path = "tests/main_tests.rs"]
 mod tests;
-mod workflow;
 
-use crate::workflow::{WorkflowError, WorkflowSummary, execute};
-use dialoguer::{Input, Select};
+use clap::{Parser, ValueEnum};
+use dialoguer::{Confirm, Input, Select};
+use duplicate_finder_rs::cli::{
+    CacheAction, CacheArgs, Cli, Command, OrganizeArgs, RunArgs, UndoArgs,
+};
+use duplicate_finder_rs::csv_report::CsvRenderer;
+use duplicate_finder_rs::duplicate_detector::DetectionStats;
+use duplicate_finder_rs::hash_cache::HashCache;
+use duplicate_finder_rs::html_report::HtmlRenderer;
+use duplicate_finder_rs::json_report::JsonRenderer;
+use duplicate_finder_rs::keeper::KeepPolicy;
+use duplicate_finder_rs::markdown_report::MarkdownRenderer;
+use duplicate_finder_rs::organizer::{DeleteMode, DuplicateAction, PlannedMove};
+use duplicate_finder_rs::report::{self, Renderer, Report};
+use duplicate_finder_rs::workflow::{
+    Detected, WorkflowError, WorkflowMode, WorkflowOptions, WorkflowSummary, detect, execute,
+    preview, resolve, restore,
+};
 use std::error::Error;
 use std::io;
+use std::path::PathBuf;
 use std::process;
 
+// What happens between finding duplicates and acting on them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Review {
+    None,
+    Confirm,
+    Interactive,
+}
+
 fn main() {
+    let cli = Cli::parse().validate().unwrap_or_else(|error| error.exit());
+
+    if let Some(command) = cli.command {
+        process::exit(run_command(command));
+    }
+
     let root_path = match prompt_for_directory() {
         Ok(path) => path,
         Err(error) => {
@@ -24,54 +53,302 @@ fn main() {
     run_application(&root_path);
 }
 
-fn run_application(root_path: &str) {
-    println!("Scanning directory: {root_path}");
-    println!("Finding duplicate files...");
+fn run_command(command: Command) -> i32 {
+    match command {
+        Command::Scan(args) => run_workflow(
+            &args,
+            WorkflowOptions {
+                mode: WorkflowMode::Scan,
+                ..WorkflowOptions::default()
+            },
+            Review::None,
+        ),
+        Command::Organize(args) => run_workflow(
+            &args.run,
+            WorkflowOptions {
+                dry_run: args.dry_run,
+                keep: args.keep_policy(),
+                action: args.action,
+                symlink_style: args.symlink_style,
+                delete_mode: args.delete_mode(),
+                ..WorkflowOptions::default()
+            },
+            organize_review(&args),
+        ),
+        Command::Report(args) => run_workflow(
+            &args,
+            WorkflowOptions {
+                mode: WorkflowMode::Report,
+                ..WorkflowOptions::default()
+            },
+            Review::None,
+        ),
+        Command::Undo(args) => run_restore(&args),
+        Command::Cache(args) => run_cache(&args),
+    }
+}
 
-    match execute(root_path) {
-        Ok(summary) => {
-            let WorkflowSummary {
-                files_scanned,
-                duplicate_group_count,
-                duplicates_found,
-                index_path,
-                index_content,
-                index_read_error,
-            } = summary;
-
-            println!("Found {files_scanned} files to process");
-            println!("Found {duplicate_group_count} groups of duplicate files");
-
-            if duplicates_found {
-                println!("Successfully organized duplicate files!");
-                println!("Check the 'duplicates' folder for organized files.");
+fn organize_review(args: &OrganizeArgs) -> Review {
+    if args.interactive {
+        Review::Interactive
+    } else if args.action == DuplicateAction::Delete && !args.dry_run && !args.yes {
+        Review::Confirm
+    } else {
+        Review::None
+    }
+}
+
+fn run_cache(args: &CacheArgs) -> i32 {
+    let cache_path = args.cache_path();
+
+    let result = match args.action {
+        CacheAction::Show => HashCache::load(&cache_path).map(|cache| print_cache(&cache)),
+        CacheAction::Prune => HashCache::load(&cache_path).and_then(|mut cache| {
+            let removed = cache.prune();
+            cache.save()?;
+            println!(
+                "Removed {removed} stale entries, {} remain in {}",
+                cache.len(),
+                cache.path().display()
+            );
+            Ok(())
+        }),
+        CacheAction::Clear => HashCache::clear(&cache_path).map(|removed| {
+            if removed {
+                println!("Deleted hash cache {}", cache_path.display());
             } else {
-                println!("No duplicate files found!");
+                println!("No hash cache at {}", cache_path.display());
             }
+        }),
+    };
+
+    match result {
+        Ok(()) => 0,
+        Err(error) => {
+            eprintln!("Error accessing hash cache: {error}");
+            1
+        }
+    }
+}
 
-            println!();
-            println!("=== COMPREHENSIVE DUPLICATE FILES INDEX ===");
+fn print_cache(cache: &HashCache) {
+    let mut per_algorithm = std::collections::BTreeMap::new();
+    let mut cached_bytes = 0u64;
+    for (_path, entry) in cache.entries() {
+        *per_algorithm
+            .entry(entry.algorithm.name())
+            .or_insert(0usize) += 1;
+        cached_bytes += entry.size;
+    }
+
+    println!("Hash cache: {}", cache.path().display());
+    println!("Cached files: {}", cache.len());
+    println!(
+        "Cached data: {:.2} MB",
+        cached_bytes as f64 / (1024.0 * 1024.0)
+    );
+    for (algorithm, count) in per_algorithm {
+        println!("  {algorithm}: {count} files");
+    }
+}
 
-            match (index_content, index_read_error) {
-                (Some(content), _) => println!("{content}"),
-                (None, Some(error)) => {
-                    eprintln!("Warning: Could not read index file: {error}");
+fn run_restore(args: &UndoArgs) -> i32 {
+    let mut exit_code = 0;
+
+    for root_path in args.root_paths() {
+        println!("Restoring organized files in: {root_path}");
+
+        match restore(&root_path) {
+            Ok(summary) => {
+                println!("Restored {} files", summary.restored.len());
+                for conflict in &summary.conflicts {
                     eprintln!(
-                        "The index file should be available at: {}",
-                        index_path.display()
+                        "Conflict: {} -> {}: {}",
+                        conflict.entry.new_path.display(),
+                        conflict.entry.original_path.display(),
+                        conflict.reason
                     );
                 }
-                (None, None) => {
-                    eprintln!("Warning: Index file could not be read.");
+                if !summary.conflicts.is_empty() {
                     eprintln!(
-                        "The index file should be available at: {}",
-                        index_path.display()
+                        "{} files could not be restored and remain in the journal",
+                        summary.conflicts.len()
                     );
+                    exit_code = 1;
+                }
+            }
+            Err(error) => {
+                handle_workflow_error(&error);
+                exit_code = 1;
+            }
+        }
+    }
+
+    exit_code
+}
+
+fn run_workflow(args: &RunArgs, options: WorkflowOptions, review: Review) -> i32 {
+    let options = WorkflowOptions {
+        scan: args.scan_options(),
+        detection: args.detection_options(),
+        verify: args.verify,
+        cache: args.cache || args.cache_file.is_some(),
+        cache_file: args.cache_file.clone(),
+        ..options
+    };
+    let human_output = !args.report_to_stdout();
+    let root_paths = args.root_paths();
+    let mut reports = Vec::new();
+    let mut exit_code = 0;
+
+    for root_path in &root_paths {
+        if human_output {
+            println!("Scanning directory: {root_path}");
+            println!("Finding duplicate files...");
+        }
+
+        let result = detect(root_path, &options).and_then(|detected| {
+            let options = match review {
+                Review::None => options.clone(),
+                Review::Confirm => {
+                    if !confirm_plan(root_path, &options, &detected)? {
+                        return Ok(None);
+                    }
+                    options.clone()
                 }
+                Review::Interactive => match review_plan(root_path, &options, &detected)? {
+                    Some(options) => options,
+                    None => return Ok(None),
+                },
+            };
+            resolve(root_path, &options, detected).map(Some)
+        });
+
+        match result {
+            Ok(Some(summary)) => {
+                if human_output {
+                    print_summary(&summary, &options, !args.quiet);
+                }
+                reports.push(summary.report);
+            }
+            Ok(None) => eprintln!("Cancelled; no files were changed."),
+            Err(error) => {
+                handle_workflow_error(&error);
+                exit_code = 1;
             }
+        }
+    }
+
+    let renderers: [(&Option<PathBuf>, &str, &dyn Renderer); 5] = [
+        (&args.json, "JSON report", &JsonRenderer),
+        (&args.csv, "CSV report", &CsvRenderer::Files),
+        (&args.csv_summary, "CSV group summary", &CsvRenderer::Groups),
+        (&args.html, "HTML report", &HtmlRenderer),
+        (&args.markdown, "Markdown report", &MarkdownRenderer),
+    ];
+    if renderers.iter().any(|(path, _, _)| path.is_some()) {
+        let report = Report::combine(reports, &options, &root_paths);
+        for (path, name, renderer) in renderers {
+            let Some(path) = path else {
+                continue;
+            };
+            match renderer
+                .render(&report)
+                .and_then(|content| report::write_output(path, &content))
+            {
+                Ok(()) if human_output => println!("{name} written to: {}", path.display()),
+                Ok(()) => {}
+                Err(error) => {
+                    eprintln!("Error writing {name}: {error}");
+                    exit_code = 1;
+                }
+            }
+        }
+    }
+
+    exit_code
+}
+
+// Lets the user decide every group in turn, then confirms the resulting plan unless it is a
+// dry run. `None` means the review was cancelled.
+fn review_plan(
+    root_path: &str,
+    options: &WorkflowOptions,
+    detected: &Detected,
+) -> Result<Option<WorkflowOptions>, WorkflowError> {
+    if detected.duplicates.is_empty() {
+        return Ok(Some(options.clone()));
+    }
 
-            println!("=== END OF INDEX ===");
-            println!();
+    let keep = options.keep.clone().unwrap_or(KeepPolicy::FirstScanned);
+    let Some(decisions) = review::review_groups(&detected.duplicates, options.action, &keep)
+        .map_err(|source| WorkflowError::Review { source })?
+    else {
+        return Ok(None);
+    };
+
+    let options = WorkflowOptions {
+        decisions,
+        ..options.clone()
+    };
+    eprintln!();
+    if options.dry_run || confirm_plan(root_path, &options, detected)? {
+        Ok(Some(options))
+    } else {
+        Ok(None)
+    }
+}
+
+// Shows how many files and bytes each action would touch and asks before going ahead
+fn confirm_plan(
+    root_path: &str,
+    options: &WorkflowOptions,
+    detected: &Detected,
+) -> Result<bool, WorkflowError> {
+    let plan = preview(root_path, options, detected)?;
+    if plan.is_empty() {
+        return Ok(true);
+    }
+
+    for (action, count, bytes) in plan_totals(&plan) {
+        eprintln!(
+            "{count} duplicate files ({:.2} MB) will be {}.",
+            bytes as f64 / (1024.0 * 1024.0),
+            action_description(action, &options.delete_mode)
+        );
+    }
+
+    Confirm::new()
+        .with_prompt("Proceed?")
+        .default(false)
+        .interact()
+        .map_err(|error| WorkflowError::Confirm {
+            source: io::Error::other(error),
+        })
+}
+
+// Files and bytes per action, in the order the actions are declared
+fn plan_totals(plan: &[PlannedMove]) -> Vec<(DuplicateAction, usize, u64)> {
+    DuplicateAction::value_variants()
+        .iter()
+        .filter_map(|action| {
+            let moves: Vec<&PlannedMove> = plan
+                .iter()
+                .filter(|planned_move| planned_move.action == *action)
+                .collect();
+            let bytes = moves.iter().map(|planned_move| planned_move.size).sum();
+            (!moves.is_empty()).then_some((*action, moves.len(), bytes))
+        })
+        .collect()
+}
+
+fn run_application(root_path: &str) {
+    println!("Scanning directory: {root_path}");
+    println!("Finding duplicate files...");
+
+    match execute(root_path) {
+        Ok(summary) => {
+            print_summary(&summary, &WorkflowOptions::default(), true);
 
             println!("Press Enter to exit...");
             if let Err(error) = wait_for_enter() {
@@ -85,6 +362,152 @@ fn run_application(root_path: &str) {
     }
 }
 
+fn print_summary(summary: &WorkflowSummary, options: &WorkflowOptions, print_index: bool) {
+    println!("Found {} files to process", summary.files_scanned);
+    print_detection_stats(&summary.detection_stats);
+    if summary.detection_stats.cache_hits > 0 {
+        println!(
+            "  Hash cache: {} full hashes reused",
+            summary.detection_stats.cache_hits
+        );
+    }
+    for issue in &summary.verification_issues {
+        eprintln!("Verification: {issue}");
+    }
+    println!(
+        "Found {} groups of duplicate files",
+        summary.duplicate_group_count
+    );
+
+    if !summary.duplicates_found {
+        println!("No duplicate files found!");
+    } else if options.mode == WorkflowMode::Organize && options.dry_run {
+        for (action, count, _) in plan_totals(&summary.plan) {
+            println!(
+                "Dry run: {count} files would be {}.",
+                action_description(action, &options.delete_mode)
+            );
+        }
+        println!("Nothing was changed.");
+    } else if options.mode == WorkflowMode::Organize {
+        println!("Successfully organized duplicate files!");
+        let totals = plan_totals(&summary.plan);
+        for (action, count, _) in &totals {
+            println!(
+                "{count} files were {}.",
+                action_description(*action, &options.delete_mode)
+            );
+        }
+        if totals
+            .iter()
+            .any(|(action, _, _)| *action == DuplicateAction::Move)
+        {
+            println!("Check the 'duplicates' folder for organized files.");
+        }
+    }
+    for organized_group in &summary.groups {
+        for skipped in &organized_group.skipped {
+            eprintln!("Skipped: {}: {}", skipped.path.display(), skipped.reason);
+        }
+    }
+
+    match options.mode {
+        WorkflowMode::Scan => print_groups(summary),
+        WorkflowMode::Report | WorkflowMode::Organize => {
+            if print_index {
+                print_index_content(summary);
+            } else {
+                println!("Index written to: {}", summary.index_path.display());
+            }
+        }
+    }
+}
+
+fn action_description(action: DuplicateAction, delete_mode: &DeleteMode) -> String {
+    match (action, delete_mode) {
+        (DuplicateAction::Move, _) => "moved into the duplicates folder".to_string(),
+        (DuplicateAction::Hardlink, _) => "replaced with hardlinks".to_string(),
+        (DuplicateAction::Symlink, _) => "replaced with symlinks".to_string(),
+        (DuplicateAction::Reflink, _) => "deduplicated with shared extents".to_string(),
+        (DuplicateAction::Delete, DeleteMode::Trash) => "moved to the trash".to_string(),
+        (DuplicateAction::Delete, DeleteMode::Quarantine(directory)) => {
+            format!("moved to {}", directory.display())
+        }
+        (DuplicateAction::Delete, DeleteMode::Permanent) => "permanently deleted".to_string(),
+    }
+}
+
+fn print_detection_stats(stats: &DetectionStats) {
+    if stats.link_loops > 0 {
+        println!(
+            "  Symlink loops: {} links lead back to a parent directory and were skipped",
+            stats.link_loops
+        );
+    }
+    if stats.linked_paths > 0 {
+        println!(
+            "  Hardlinks: {} paths share a file with another path and were counted once",
+            stats.linked_paths
+        );
+    }
+
+    let stages = [
+        ("Size comparison", stats.size),
+        ("Partial hash", stats.partial),
+        ("Full hash", stats.full),
+    ];
+
+    for (name, stage) in stages {
+        if stage.candidates > 0 {
+            println!(
+                "  {name}: {} of {} candidates eliminated",
+                stage.eliminated, stage.candidates
+            );
+        }
+    }
+}
+
+fn print_groups(summary: &WorkflowSummary) {
+    for (i, organized_group) in summary.groups.iter().enumerate() {
+        println!();
+        if organized_group.is_empty_files() {
+            println!("Group {} (empty files):", i + 1);
+        } else {
+            println!("Group {} ({}):", i + 1, organized_group.hash);
+        }
+        for file_path in &organized_group.group.files {
+            println!("  - {}", file_path.display());
+        }
+    }
+    println!();
+}
+
+fn print_index_content(summary: &WorkflowSummary) {
+    println!();
+    println!("=== COMPREHENSIVE DUPLICATE FILES INDEX ===");
+
+    match (&summary.index_content, &summary.index_read_error) {
+        (Some(content), _) => println!("{content}"),
+        (None, Some(error)) => {
+            eprintln!("Warning: Could not read index file: {error}");
+            eprintln!(
+                "The index file should be available at: {}",
+                summary.index_path.display()
+            );
+        }
+        (None, None) => {
+            eprintln!("Warning: Index file could not be read.");
+            eprintln!(
+                "The index file should be available at: {}",
+                summary.index_path.display()
+            );
+        }
+    }
+
+    println!("=== END OF INDEX ===");
+    println!();
+}
+
 fn handle_workflow_error(error: &WorkflowError) {
     eprintln!("{error}");
     if let Some(source) = error.source() {
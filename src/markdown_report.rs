@@ -0,0 +1,119 @@
+use crate::report::{Renderer, Report};
+use std::io;
+
+// GitHub-flavoured Markdown, for pasting into issues and wikis
+pub struct MarkdownRenderer;
+
+impl Renderer for MarkdownRenderer {
+    fn render(&self, report: &Report) -> Result<String, io::Error> {
+        let run = &report.run;
+        let totals = &report.totals;
+        let mut markdown = String::new();
+        markdown.push_str("# Duplicate files report\n\n");
+
+        let roots: Vec<String> = run.roots.iter().map(|root| escape(root)).collect();
+        markdown.push_str("| | |\n|---|---|\n");
+        markdown.push_str(&format!("| Scanned | {} |\n", roots.join(", ")));
+        markdown.push_str(&format!(
+            "| Generated | {} |\n",
+            run.generated_at.format("%Y-%m-%d %H:%M:%S UTC")
+        ));
+        markdown.push_str(&format!("| Mode | {} |\n", run.mode.name()));
+        if let Some(action) = run.action {
+            let dry_run = if run.dry_run { " (dry run)" } else { "" };
+            markdown.push_str(&format!("| Action | {action}{dry_run} |\n"));
+        }
+        markdown.push_str(&format!("| Hash algorithm | {} |\n", run.algorithm));
+        markdown.push_str(&format!("| Files scanned | {} |\n", totals.files_scanned));
+        markdown.push_str(&format!(
+            "| Duplicate groups | {} |\n",
+            totals.duplicate_groups
+        ));
+        markdown.push_str(&format!(
+            "| Duplicate files | {} |\n",
+            totals.duplicate_files
+        ));
+        markdown.push_str(&format!(
+            "| Duplicate data | {} bytes ({} bytes allocated) |\n",
+            totals.logical_bytes, totals.allocated_bytes
+        ));
+        markdown.push_str(&format!(
+            "| Redundant copies | {} ({} bytes) |\n",
+            totals.redundant_files, totals.redundant_bytes
+        ));
+        markdown.push_str(&format!(
+            "| Reclaimable on disk | {} bytes |\n",
+            totals.reclaimable_bytes
+        ));
+        if run.action.is_some() {
+            markdown.push_str(&format!(
+                "| Acted on | {} files ({} bytes) |\n",
+                totals.files_acted_on, totals.bytes_acted_on
+            ));
+        }
+        markdown.push('\n');
+
+        for (index, group) in report.groups.iter().enumerate() {
+            markdown.push_str(&format!(
+                "## Group {}: {} copies of {} bytes\n\n",
+                index + 1,
+                group.files.len(),
+                group.size
+            ));
+            markdown.push_str(&format!(
+                "Hash: `{}` ({}), {} bytes allocated, {} bytes reclaimable",
+                group.hash, group.algorithm, group.allocated_bytes, group.reclaimable_bytes
+            ));
+            if group.skipped_during_review {
+                markdown.push_str(", skipped during review");
+            } else if let Some(action) = group.action {
+                markdown.push_str(&format!(", action: {action}"));
+            }
+            markdown.push_str("\n\n| Path | Modified (UTC) | Outcome |\n|---|---|---|\n");
+
+            for file in &group.files {
+                let modified = file
+                    .modified
+                    .map(|modified| modified.format("%Y-%m-%d %H:%M:%S").to_string())
+                    .unwrap_or_default();
+                let outcome = if file.keeper {
+                    "kept".to_string()
+                } else if let Some(reason) = &file.skipped {
+                    format!("skipped: {}", escape(reason))
+                } else if let Some(destination) = &file.destination {
+                    if destination.as_os_str().is_empty() {
+                        "removed".to_string()
+                    } else {
+                        format!("→ {}", escape(&destination.to_string_lossy()))
+                    }
+                } else {
+                    String::new()
+                };
+                markdown.push_str(&format!(
+                    "| {} | {modified} | {outcome} |\n",
+                    escape(&file.path.to_string_lossy())
+                ));
+            }
+            markdown.push('\n');
+        }
+
+        Ok(markdown)
+    }
+}
+
+// Backslash-escapes everything Markdown could read as formatting, and flattens line breaks so
+// a file name cannot end a table row
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|' | '!' | '~' => {
+                escaped.push('\\');
+                escaped.push(character);
+            }
+            '\n' | '\r' => escaped.push(' '),
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
@@ -1,7 +1,14 @@
+use crate::artifacts::DUPLICATES_FOLDER_NAME;
 use crate::duplicate_detector::DuplicateGroup;
+use crate::file_scanner::{self, FileInfo};
 use crate::hasher::Hash;
+use crate::journal::JournalWriter;
+use crate::keeper::KeepPolicy;
+use crate::linker;
+use crate::trash::Trash;
+use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -9,14 +16,124 @@ use std::path::{Path, PathBuf};
 pub struct Organizer {
     pub root_path: PathBuf,
     skip_paths: HashSet<PathBuf>,
+    options: OrganizeOptions,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateAction {
+    // Move redundant copies into the `duplicates` folder
+    #[default]
+    Move,
+    // Replace redundant copies with hardlinks to the kept file
+    Hardlink,
+    // Replace redundant copies with symbolic links to the kept file
+    Symlink,
+    // Share the kept file's extents with redundant copies (Linux, copy-on-write filesystems)
+    Reflink,
+    // Remove redundant copies, by default into the trash
+    Delete,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SymlinkStyle {
+    // Link targets are relative to the link's directory, so moving the whole tree keeps them valid
+    #[default]
+    Relative,
+    Absolute,
+}
+
+impl DuplicateAction {
+    pub fn name(self) -> &'static str {
+        match self {
+            DuplicateAction::Move => "move",
+            DuplicateAction::Hardlink => "hardlink",
+            DuplicateAction::Symlink => "symlink",
+            DuplicateAction::Reflink => "reflink",
+            DuplicateAction::Delete => "delete",
+        }
+    }
+}
+
+impl std::fmt::Display for DuplicateAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum DeleteMode {
+    // The user's freedesktop.org Trash, where file managers can restore from
+    #[default]
+    Trash,
+    // A folder that mirrors the scanned tree
+    Quarantine(PathBuf),
+    // Unlink for good; the only mode `undo` cannot reverse
+    Permanent,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OrganizeOptions {
+    // Plan every move and write the index, but create no folders and move nothing
+    pub dry_run: bool,
+    // Leave the selected file of each group in place; `None` moves every file. Actions other
+    // than `Move` always keep a file, the first scanned one unless a policy says otherwise.
+    pub keep: Option<KeepPolicy>,
+    pub action: DuplicateAction,
+    pub symlink_style: SymlinkStyle,
+    pub delete_mode: DeleteMode,
+    // Per-group choices made during review; groups without one use `action` and `keep`
+    pub decisions: BTreeMap<Hash, GroupDecision>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupDecision {
+    // Leave every file of the group untouched
+    Skip,
+    Apply {
+        action: DuplicateAction,
+        keeper: PathBuf,
+    },
+}
+
+// For `Move` the destination is the new location inside `duplicates`; for link actions it is
+// the kept file the source now points at. Deleted files point into the trash, or nowhere (an
+// empty path) when deleted permanently.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedMove {
+    pub source: PathBuf,
+    pub destination: PathBuf,
+    pub hash: Hash,
+    pub size: u64,
+    pub action: DuplicateAction,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedFile {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+#[derive(Debug, Default)]
 pub struct OrganizedGroup {
     pub hash: Hash,
     pub group: DuplicateGroup,
     pub folder: PathBuf,
-    pub file_sizes: Vec<u64>,
+    // Metadata of every file in the group, taken before the action
+    pub file_infos: Vec<FileInfo>,
+    pub keeper: Option<PathBuf>,
+    pub action: DuplicateAction,
+    pub moves: Vec<PlannedMove>,
+    // Files the action could not be applied to; they are left untouched
+    pub skipped: Vec<SkippedFile>,
+    // The whole group was skipped during review
+    pub untouched: bool,
+}
+
+impl OrganizedGroup {
+    pub fn is_empty_files(&self) -> bool {
+        !self.file_infos.is_empty() && self.file_infos.iter().all(|file_info| file_info.size == 0)
+    }
 }
 
 impl Organizer {
@@ -24,9 +141,15 @@ impl Organizer {
         Organizer {
             root_path: root_path.as_ref().to_path_buf(),
             skip_paths: HashSet::new(),
+            options: OrganizeOptions::default(),
         }
     }
 
+    pub fn with_options(mut self, options: OrganizeOptions) -> Self {
+        self.options = options;
+        self
+    }
+
     pub fn with_skip_paths<P: AsRef<Path>, I>(root_path: P, skip_paths: I) -> Self
     where
         I: IntoIterator<Item = PathBuf>,
@@ -48,40 +171,174 @@ impl Organizer {
 
     pub fn organize_duplicates(
         &self,
-        duplicates: HashMap<Hash, DuplicateGroup>,
+        duplicates: BTreeMap<Hash, DuplicateGroup>,
     ) -> Result<Vec<OrganizedGroup>, std::io::Error> {
         let mut organized_groups = Vec::new();
+        // Destinations already handed out in this run; in a dry run nothing lands on disk,
+        // so `exists()` alone cannot tell two planned files with the same name apart
+        let mut reserved = HashSet::new();
+        // Every change is journaled so `restore` can replay the run backwards; the journal is
+        // only created once there is something to record
+        let mut journal: Option<JournalWriter> = None;
+        let mut trash: Option<Trash> = None;
 
         for (hash, group) in duplicates {
-            if let Some(original_file) = group.files.first() {
-                let original_filename = original_file
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("unknown");
-
-                let folder_name = format!("{hash}_{original_filename}");
-                let duplicate_folder = self.root_path.join("duplicates").join(folder_name);
+            if let Some(duplicate_folder) = self.duplicate_folder(&hash, &group) {
+                let (action, keeper) = match self.decision(&hash, &group) {
+                    Some(GroupDecision::Skip) => {
+                        organized_groups.push(OrganizedGroup {
+                            hash,
+                            folder: duplicate_folder,
+                            file_infos: file_infos(&group)?,
+                            group,
+                            untouched: true,
+                            ..OrganizedGroup::default()
+                        });
+                        continue;
+                    }
+                    Some(GroupDecision::Apply { action, keeper }) => {
+                        (*action, Some(keeper.clone()))
+                    }
+                    None => (self.options.action, self.select_keeper(&group)?),
+                };
 
                 // Create the duplicate folder
-                fs::create_dir_all(&duplicate_folder)?;
+                if !self.options.dry_run && action == DuplicateAction::Move {
+                    fs::create_dir_all(&duplicate_folder)?;
+                }
+                if action == DuplicateAction::Delete && trash.is_none() {
+                    trash = self.trash()?;
+                }
 
-                let mut file_sizes = Vec::new();
+                let mut file_infos = Vec::new();
+                let mut moves = Vec::new();
+                let mut skipped = Vec::new();
+                // Only a review decision can name a link; it must not stand in for the data
+                let keeper_is_symlink = keeper.as_deref().is_some_and(file_scanner::is_symlink);
 
-                // Move all files in the group to the duplicate folder
+                // Apply the action to every file except the keeper
                 for file_path in &group.files {
-                    let size = fs::metadata(file_path)?.len();
-                    file_sizes.push(size);
+                    let file_info = FileInfo::new(file_path.clone())?;
+                    let size = file_info.size;
+                    let is_symlink = file_info.is_symlink;
+                    file_infos.push(file_info);
 
-                    if self.should_skip(file_path) {
+                    if self.should_skip(file_path) || keeper.as_ref() == Some(file_path) {
+                        continue;
+                    }
+                    if is_symlink || keeper_is_symlink {
+                        skipped.push(SkippedFile {
+                            path: file_path.clone(),
+                            reason: if is_symlink {
+                                "is a symbolic link"
+                            } else {
+                                "the kept file is a symbolic link"
+                            }
+                            .to_string(),
+                        });
                         continue;
                     }
 
-                    let file_name = file_path.file_name().ok_or_else(|| {
-                        std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid file name")
-                    })?;
+                    let apply = !self.options.dry_run;
+                    let destination = match (action, &keeper) {
+                        (DuplicateAction::Move, _) => {
+                            let file_name = file_path.file_name().ok_or_else(|| {
+                                std::io::Error::new(
+                                    std::io::ErrorKind::InvalidData,
+                                    "Invalid file name",
+                                )
+                            })?;
+
+                            let new_path = self.build_unique_destination(
+                                &duplicate_folder,
+                                file_name,
+                                &reserved,
+                            );
+                            if apply {
+                                fs::rename(file_path, &new_path)?;
+                            }
+                            reserved.insert(new_path.clone());
+                            new_path
+                        }
+                        (DuplicateAction::Hardlink, Some(keeper)) => {
+                            if !linker::same_filesystem(keeper, file_path)? {
+                                skipped.push(SkippedFile {
+                                    path: file_path.clone(),
+                                    reason: "on a different filesystem than the kept file"
+                                        .to_string(),
+                                });
+                                continue;
+                            }
+                            if apply {
+                                linker::replace_with_hardlink(keeper, file_path)?;
+                            }
+                            keeper.clone()
+                        }
+                        (DuplicateAction::Symlink, Some(keeper)) => {
+                            if apply {
+                                linker::replace_with_symlink(
+                                    keeper,
+                                    file_path,
+                                    self.options.symlink_style == SymlinkStyle::Relative,
+                                )?;
+                            }
+                            keeper.clone()
+                        }
+                        (DuplicateAction::Reflink, Some(keeper)) => {
+                            // Any failure only means the copy keeps its own extents
+                            if apply && let Err(error) = linker::share_extents(keeper, file_path) {
+                                skipped.push(SkippedFile {
+                                    path: file_path.clone(),
+                                    reason: error.to_string(),
+                                });
+                                continue;
+                            }
+                            keeper.clone()
+                        }
+                        (DuplicateAction::Delete, Some(_)) => match &trash {
+                            Some(trash) if !apply => trash.directory().to_path_buf(),
+                            Some(trash) => {
+                                let relative_path = file_path
+                                    .strip_prefix(&self.root_path)
+                                    .ok()
+                                    .filter(|relative| relative.file_name().is_some())
+                                    .unwrap_or_else(|| {
+                                        Path::new(file_path.file_name().unwrap_or_default())
+                                    });
+                                trash.put(file_path, relative_path)?
+                            }
+                            None => {
+                                if apply {
+                                    fs::remove_file(file_path)?;
+                                }
+                                PathBuf::new()
+                            }
+                        },
+                        (_, None) => continue,
+                    };
+
+                    if apply && self.is_reversible(action) {
+                        if journal.is_none() {
+                            journal = Some(JournalWriter::open(&self.root_path)?);
+                        }
+                        if let Some(journal) = journal.as_mut() {
+                            journal.record(
+                                file_path,
+                                &destination,
+                                &hash,
+                                group.algorithm,
+                                action,
+                            )?;
+                        }
+                    }
 
-                    let new_path = self.build_unique_destination(&duplicate_folder, file_name);
-                    fs::rename(file_path, &new_path)?;
+                    moves.push(PlannedMove {
+                        source: file_path.clone(),
+                        destination,
+                        hash: hash.clone(),
+                        size,
+                        action,
+                    });
                 }
 
                 // Store information for comprehensive index
@@ -89,7 +346,12 @@ impl Organizer {
                     hash: hash.clone(),
                     group,
                     folder: duplicate_folder,
-                    file_sizes,
+                    file_infos,
+                    keeper,
+                    action,
+                    moves,
+                    skipped,
+                    untouched: false,
                 });
             }
         }
@@ -97,71 +359,98 @@ impl Organizer {
         Ok(organized_groups)
     }
 
-    pub fn create_comprehensive_index(
+    // Collects the same information as `organize_duplicates` without touching any files
+    pub fn describe_duplicates(
         &self,
-        organized_groups: &[OrganizedGroup],
-    ) -> Result<(), std::io::Error> {
-        let index_path = self.root_path.join("duplicate_files_index.txt");
-
-        let mut index_content = String::new();
-        index_content.push_str("Duplicate Files Comprehensive Index\n");
-        index_content.push_str("===================================\n\n");
-        index_content.push_str(&format!(
-            "Total duplicate groups found: {}\n",
-            organized_groups.len()
-        ));
-        index_content.push_str(&format!(
-            "Index created: {}\n",
-            chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
-        ));
-        index_content.push_str(&format!(
-            "Scanned directory: {}\n\n",
-            self.root_path.display()
-        ));
-
-        let total_files = organized_groups
-            .iter()
-            .map(|group| group.group.files.len())
-            .sum::<usize>();
+        duplicates: BTreeMap<Hash, DuplicateGroup>,
+    ) -> Result<Vec<OrganizedGroup>, std::io::Error> {
+        let mut described_groups = Vec::new();
 
-        let freed_bytes = organized_groups
-            .iter()
-            .map(|group| {
-                let total: u64 = group.file_sizes.iter().copied().sum();
-                let keep = group.file_sizes.iter().copied().min().unwrap_or(0);
-                total.saturating_sub(keep)
-            })
-            .sum::<u64>();
-
-        let freed_mb = freed_bytes as f64 / (1024.0 * 1024.0);
-
-        index_content.push_str(&format!("Total files in duplicate groups: {total_files}\n"));
-        index_content.push_str(&format!(
-            "Space that can be freed: Approximately {freed_mb:.2} MB (estimated)\n\n"
-        ));
-
-        index_content.push_str("Duplicate Groups:\n");
-        index_content.push_str("================\n\n");
-
-        for (i, organized_group) in organized_groups.iter().enumerate() {
-            let hash = &organized_group.hash;
-            let group = &organized_group.group;
-            let folder_path = &organized_group.folder;
-            index_content.push_str(&format!("Group {}:\n", i + 1));
-            index_content.push_str(&format!("  Hash: {hash}\n"));
-            index_content.push_str(&format!("  Folder: {}\n", folder_path.display()));
-            index_content.push_str(&format!("  Files in group: {}\n", group.files.len()));
-            index_content.push_str("  File paths:\n");
-
-            for file_path in &group.files {
-                index_content.push_str(&format!("    - {}\n", file_path.display()));
+        for (hash, group) in duplicates {
+            if let Some(duplicate_folder) = self.duplicate_folder(&hash, &group) {
+                let file_infos = file_infos(&group)?;
+                let (action, keeper, untouched) = match self.decision(&hash, &group) {
+                    Some(GroupDecision::Skip) => (self.options.action, None, true),
+                    Some(GroupDecision::Apply { action, keeper }) => {
+                        (*action, Some(keeper.clone()), false)
+                    }
+                    None => (self.options.action, self.select_keeper(&group)?, false),
+                };
+
+                described_groups.push(OrganizedGroup {
+                    hash,
+                    group,
+                    folder: duplicate_folder,
+                    file_infos,
+                    keeper,
+                    action,
+                    untouched,
+                    ..OrganizedGroup::default()
+                });
             }
+        }
+
+        Ok(described_groups)
+    }
 
-            index_content.push('\n');
+    // A keeper that changed after the review is no longer part of its group; nothing in the
+    // group may then be replaced by or linked to it
+    fn decision(&self, hash: &Hash, group: &DuplicateGroup) -> Option<&GroupDecision> {
+        match self.options.decisions.get(hash) {
+            Some(GroupDecision::Apply { keeper, .. }) if !group.files.contains(keeper) => {
+                Some(&GroupDecision::Skip)
+            }
+            decision => decision,
         }
+    }
 
-        fs::write(index_path, index_content)?;
-        Ok(())
+    fn duplicate_folder(&self, hash: &Hash, group: &DuplicateGroup) -> Option<PathBuf> {
+        let original_file = group.files.first()?;
+        let original_filename = original_file
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown");
+
+        let folder_name = format!("{hash}_{original_filename}");
+        Some(
+            self.root_path
+                .join(DUPLICATES_FOLDER_NAME)
+                .join(folder_name),
+        )
+    }
+
+    // Shared extents leave each file intact and independent, so there is nothing to undo for
+    // `Reflink`, and permanent deletion cannot be undone at all
+    fn is_reversible(&self, action: DuplicateAction) -> bool {
+        match action {
+            DuplicateAction::Reflink => false,
+            DuplicateAction::Delete => self.options.delete_mode != DeleteMode::Permanent,
+            _ => true,
+        }
+    }
+
+    fn trash(&self) -> Result<Option<Trash>, std::io::Error> {
+        Ok(match &self.options.delete_mode {
+            DeleteMode::Trash => Some(Trash::home()?),
+            DeleteMode::Quarantine(directory) => Some(Trash::quarantine(directory)),
+            DeleteMode::Permanent => None,
+        })
+    }
+
+    // Symlinks reached with `--follow-symlinks` are never kept; the data they point at may lie
+    // outside the tree
+    fn select_keeper(&self, group: &DuplicateGroup) -> Result<Option<PathBuf>, std::io::Error> {
+        let candidates: Vec<PathBuf> = group
+            .files
+            .iter()
+            .filter(|path| !file_scanner::is_symlink(path))
+            .cloned()
+            .collect();
+        match (&self.options.keep, self.options.action) {
+            (Some(policy), _) => Ok(policy.select(&candidates)?.cloned()),
+            (None, DuplicateAction::Move) => Ok(None),
+            (None, _) => Ok(KeepPolicy::FirstScanned.select(&candidates)?.cloned()),
+        }
     }
 
     fn should_skip(&self, path: &Path) -> bool {
@@ -170,39 +459,64 @@ impl Organizer {
         }
 
         let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
-        self.skip_paths.contains(&canonical)
+        self.skip_paths
+            .iter()
+            .any(|skip_path| canonical.starts_with(skip_path))
     }
 
     fn build_unique_destination(
         &self,
         directory: &Path,
         original_name: &std::ffi::OsStr,
+        reserved: &HashSet<PathBuf>,
     ) -> PathBuf {
-        let original_path = Path::new(original_name);
-        let mut candidate = directory.join(original_path);
-        if !candidate.exists() {
-            return candidate;
-        }
+        unique_destination(directory, original_name, |candidate| {
+            candidate.exists() || reserved.contains(candidate)
+        })
+    }
+}
 
-        let stem = original_path
-            .file_stem()
-            .map(|s| s.to_string_lossy())
-            .unwrap_or_else(|| Cow::from("file"));
-        let extension = original_path.extension().map(|ext| ext.to_string_lossy());
-
-        let mut index = 1;
-        loop {
-            let new_name = match &extension {
-                Some(ext) if !ext.is_empty() => format!("{stem}_copy{index}.{ext}"),
-                _ => format!("{stem}_copy{index}"),
-            };
-
-            candidate = directory.join(new_name);
-            if !candidate.exists() {
-                break candidate;
-            }
+fn file_infos(group: &DuplicateGroup) -> Result<Vec<FileInfo>, std::io::Error> {
+    group
+        .files
+        .iter()
+        .map(|file_path| FileInfo::new(file_path.clone()))
+        .collect()
+}
+
+// Picks `directory/original_name`, or `stem_copyN.ext` with the lowest free N
+pub fn unique_destination<F>(
+    directory: &Path,
+    original_name: &std::ffi::OsStr,
+    is_taken: F,
+) -> PathBuf
+where
+    F: Fn(&Path) -> bool,
+{
+    let original_path = Path::new(original_name);
+    let mut candidate = directory.join(original_path);
+    if !is_taken(&candidate) {
+        return candidate;
+    }
 
-            index += 1;
+    let stem = original_path
+        .file_stem()
+        .map(|s| s.to_string_lossy())
+        .unwrap_or_else(|| Cow::from("file"));
+    let extension = original_path.extension().map(|ext| ext.to_string_lossy());
+
+    let mut index = 1;
+    loop {
+        let new_name = match &extension {
+            Some(ext) if !ext.is_empty() => format!("{stem}_copy{index}.{ext}"),
+            _ => format!("{stem}_copy{index}"),
+        };
+
+        candidate = directory.join(new_name);
+        if !is_taken(&candidate) {
+            break candidate;
         }
+
+        index += 1;
     }
 }
@@ -0,0 +1,124 @@
+use globset::{GlobBuilder, GlobMatcher};
+use regex::Regex;
+use std::io;
+use std::path::Path;
+
+#[derive(Debug, Clone)]
+enum Pattern {
+    // Patterns without a `/` match the file name at any depth, like in .gitignore
+    NameGlob {
+        matcher: GlobMatcher,
+        dir_only: bool,
+    },
+    // Patterns with a `/` match the path relative to the scan root
+    PathGlob {
+        matcher: GlobMatcher,
+        dir_only: bool,
+    },
+    // Regexes match if they find a match in either the relative path or the file name
+    Regex(Regex),
+}
+
+impl Pattern {
+    fn glob(pattern: &str) -> Result<Self, io::Error> {
+        let dir_only = pattern.ends_with('/');
+        let trimmed = pattern.trim_end_matches('/');
+        let anchored = trimmed.contains('/');
+        let trimmed = trimmed.trim_start_matches('/');
+
+        let matcher = GlobBuilder::new(trimmed)
+            .literal_separator(true)
+            .build()
+            .map_err(|error| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Invalid glob pattern '{pattern}': {error}"),
+                )
+            })?
+            .compile_matcher();
+
+        Ok(if anchored {
+            Pattern::PathGlob { matcher, dir_only }
+        } else {
+            Pattern::NameGlob { matcher, dir_only }
+        })
+    }
+
+    fn regex(pattern: &str) -> Result<Self, io::Error> {
+        Regex::new(pattern).map(Pattern::Regex).map_err(|error| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid regex pattern '{pattern}': {error}"),
+            )
+        })
+    }
+
+    fn matches(&self, relative_path: &str, file_name: &str, is_dir: bool) -> bool {
+        match self {
+            Pattern::NameGlob { matcher, dir_only } => {
+                (is_dir || !dir_only) && matcher.is_match(file_name)
+            }
+            Pattern::PathGlob { matcher, dir_only } => {
+                (is_dir || !dir_only) && matcher.is_match(relative_path)
+            }
+            Pattern::Regex(regex) => regex.is_match(relative_path) || regex.is_match(file_name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PathFilter {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+}
+
+impl PathFilter {
+    pub fn new(
+        include_globs: &[String],
+        exclude_globs: &[String],
+        include_regexes: &[String],
+        exclude_regexes: &[String],
+    ) -> Result<Self, io::Error> {
+        let compile = |globs: &[String], regexes: &[String]| -> Result<Vec<Pattern>, io::Error> {
+            globs
+                .iter()
+                .map(|pattern| Pattern::glob(pattern))
+                .chain(regexes.iter().map(|pattern| Pattern::regex(pattern)))
+                .collect()
+        };
+
+        Ok(PathFilter {
+            include: compile(include_globs, include_regexes)?,
+            exclude: compile(exclude_globs, exclude_regexes)?,
+        })
+    }
+
+    // Excluded directories are pruned. Include patterns only apply to files, since any
+    // directory could still contain a file that matches.
+    pub fn allows(&self, relative_path: &Path, is_dir: bool) -> bool {
+        let file_name = relative_path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        let relative_path = relative_path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        if self
+            .exclude
+            .iter()
+            .any(|pattern| pattern.matches(&relative_path, &file_name, is_dir))
+        {
+            return false;
+        }
+
+        is_dir
+            || self.include.is_empty()
+            || self
+                .include
+                .iter()
+                .any(|pattern| pattern.matches(&relative_path, &file_name, is_dir))
+    }
+}
@@ -0,0 +1,278 @@
+use crate::hasher::{Hash, HashAlgorithm};
+use crate::organizer::{DeleteMode, DuplicateAction, OrganizedGroup, SymlinkStyle};
+use crate::workflow::{WorkflowMode, WorkflowOptions};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// Bumped whenever a field is renamed or removed or its meaning changes; new fields may be
+// added without a bump, so consumers should ignore keys they do not know
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+// Everything a run found and did, independent of how it is presented
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub schema_version: u32,
+    pub run: RunMetadata,
+    pub totals: Totals,
+    pub groups: Vec<GroupReport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunMetadata {
+    pub tool: &'static str,
+    pub version: &'static str,
+    pub generated_at: DateTime<Utc>,
+    pub mode: WorkflowMode,
+    pub dry_run: bool,
+    // Only set when files are organized
+    pub action: Option<DuplicateAction>,
+    // How the action was carried out, such as where deleted files went
+    pub action_details: Option<String>,
+    pub algorithm: HashAlgorithm,
+    pub roots: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Totals {
+    pub files_scanned: usize,
+    pub duplicate_groups: usize,
+    pub duplicate_files: usize,
+    // Every copy beyond the first in each group
+    pub redundant_files: usize,
+    pub redundant_bytes: u64,
+    // Summed over all groups; see `GroupReport`
+    pub logical_bytes: u64,
+    pub allocated_bytes: u64,
+    pub reclaimable_bytes: u64,
+    // Files the action was applied to, or would be in a dry run
+    pub files_acted_on: usize,
+    pub bytes_acted_on: u64,
+    pub files_skipped: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupReport {
+    pub root: String,
+    pub hash: Hash,
+    pub algorithm: HashAlgorithm,
+    // Size of each file in the group
+    pub size: u64,
+    pub keeper: Option<PathBuf>,
+    // `None` when nothing was organized or the group was skipped during review
+    pub action: Option<DuplicateAction>,
+    pub skipped_during_review: bool,
+    // Sizes of all files together, and the space the filesystem allocated for them
+    pub logical_bytes: u64,
+    pub allocated_bytes: u64,
+    // Allocated space that removing the redundant copies would free. A copy with hardlinks
+    // elsewhere frees nothing, since its data stays reachable through the other links.
+    pub reclaimable_bytes: u64,
+    // The group's folder inside `duplicates`, when its files are moved there
+    pub folder: Option<PathBuf>,
+    pub files: Vec<FileReport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub size: u64,
+    pub allocated: u64,
+    pub links: u64,
+    pub modified: Option<DateTime<Utc>>,
+    pub keeper: bool,
+    // Where the file went or what it now links to; empty for permanent deletions
+    pub destination: Option<PathBuf>,
+    // Why the action could not be applied to this file
+    pub skipped: Option<String>,
+}
+
+// Turns a report into one output format
+pub trait Renderer {
+    fn render(&self, report: &Report) -> Result<String, io::Error>;
+}
+
+impl Report {
+    pub fn build(
+        root_path: &str,
+        options: &WorkflowOptions,
+        files_scanned: usize,
+        groups: &[OrganizedGroup],
+    ) -> Report {
+        let organizing = options.mode == WorkflowMode::Organize;
+        let groups: Vec<GroupReport> = groups
+            .iter()
+            .map(|organized_group| group_report(root_path, organized_group, organizing))
+            .collect();
+
+        let mut totals = Totals {
+            files_scanned,
+            duplicate_groups: groups.len(),
+            ..Totals::default()
+        };
+        for group in &groups {
+            let redundant_files = group.files.len().saturating_sub(1);
+            totals.duplicate_files += group.files.len();
+            totals.redundant_files += redundant_files;
+            totals.redundant_bytes += group.size * redundant_files as u64;
+            totals.logical_bytes += group.logical_bytes;
+            totals.allocated_bytes += group.allocated_bytes;
+            totals.reclaimable_bytes += group.reclaimable_bytes;
+            for file in &group.files {
+                if file.skipped.is_some() {
+                    totals.files_skipped += 1;
+                } else if file.destination.is_some() {
+                    totals.files_acted_on += 1;
+                    totals.bytes_acted_on += file.size;
+                }
+            }
+        }
+
+        Report {
+            schema_version: REPORT_SCHEMA_VERSION,
+            run: RunMetadata {
+                tool: env!("CARGO_PKG_NAME"),
+                version: env!("CARGO_PKG_VERSION"),
+                generated_at: Utc::now(),
+                mode: options.mode,
+                dry_run: organizing && options.dry_run,
+                action: organizing.then_some(options.action),
+                action_details: organizing.then(|| action_details(options)).flatten(),
+                algorithm: options.detection.algorithm,
+                roots: vec![root_path.to_string()],
+            },
+            totals,
+            groups,
+        }
+    }
+
+    // Folds the reports of several roots into one. When no root produced a report, because
+    // each failed or was cancelled, the result still names the roots but has no groups.
+    pub fn combine(
+        reports: Vec<Report>,
+        options: &WorkflowOptions,
+        root_paths: &[String],
+    ) -> Report {
+        let mut reports = reports.into_iter();
+        let Some(mut combined) = reports.next() else {
+            let mut empty = Report::build("", options, 0, &[]);
+            empty.run.roots = root_paths.to_vec();
+            return empty;
+        };
+        for report in reports {
+            combined.run.roots.extend(report.run.roots);
+            let totals = &mut combined.totals;
+            totals.files_scanned += report.totals.files_scanned;
+            totals.duplicate_groups += report.totals.duplicate_groups;
+            totals.duplicate_files += report.totals.duplicate_files;
+            totals.redundant_files += report.totals.redundant_files;
+            totals.redundant_bytes += report.totals.redundant_bytes;
+            totals.logical_bytes += report.totals.logical_bytes;
+            totals.allocated_bytes += report.totals.allocated_bytes;
+            totals.reclaimable_bytes += report.totals.reclaimable_bytes;
+            totals.files_acted_on += report.totals.files_acted_on;
+            totals.bytes_acted_on += report.totals.bytes_acted_on;
+            totals.files_skipped += report.totals.files_skipped;
+            combined.groups.extend(report.groups);
+        }
+        combined
+    }
+}
+
+impl GroupReport {
+    pub fn is_empty_files(&self) -> bool {
+        self.size == 0
+    }
+
+    // Every copy but the keeper, or every copy but the first when nothing is kept
+    pub fn redundant_files(&self) -> impl Iterator<Item = &FileReport> {
+        let has_keeper = self.files.iter().any(|file| file.keeper);
+        self.files
+            .iter()
+            .enumerate()
+            .filter(move |(index, file)| if has_keeper { !file.keeper } else { *index > 0 })
+            .map(|(_, file)| file)
+    }
+}
+
+fn action_details(options: &WorkflowOptions) -> Option<String> {
+    match options.action {
+        DuplicateAction::Symlink => Some(match options.symlink_style {
+            SymlinkStyle::Relative => "relative targets".to_string(),
+            SymlinkStyle::Absolute => "absolute targets".to_string(),
+        }),
+        DuplicateAction::Delete => Some(match &options.delete_mode {
+            DeleteMode::Trash => "moved to the trash".to_string(),
+            DeleteMode::Quarantine(directory) => format!("moved to {}", directory.display()),
+            DeleteMode::Permanent => "removed permanently".to_string(),
+        }),
+        _ => None,
+    }
+}
+
+fn group_report(root: &str, organized_group: &OrganizedGroup, organizing: bool) -> GroupReport {
+    let action = (organizing && !organized_group.untouched).then_some(organized_group.action);
+    let files: Vec<FileReport> = organized_group
+        .group
+        .files
+        .iter()
+        .enumerate()
+        .map(|(index, path)| {
+            let file_info = organized_group.file_infos.get(index);
+            FileReport {
+                path: path.clone(),
+                size: file_info.map_or(0, |file_info| file_info.size),
+                allocated: file_info.map_or(0, |file_info| file_info.allocated),
+                links: file_info.map_or(1, |file_info| file_info.links),
+                modified: file_info
+                    .and_then(|file_info| file_info.modified)
+                    .map(DateTime::<Utc>::from),
+                keeper: organized_group.keeper.as_ref() == Some(path),
+                destination: organized_group
+                    .moves
+                    .iter()
+                    .find(|planned_move| &planned_move.source == path)
+                    .map(|planned_move| planned_move.destination.clone()),
+                skipped: organized_group
+                    .skipped
+                    .iter()
+                    .find(|skipped| &skipped.path == path)
+                    .map(|skipped| skipped.reason.clone()),
+            }
+        })
+        .collect();
+
+    let mut group = GroupReport {
+        root: root.to_string(),
+        hash: organized_group.hash.clone(),
+        algorithm: organized_group.group.algorithm,
+        size: files.first().map_or(0, |file| file.size),
+        logical_bytes: files.iter().map(|file| file.size).sum(),
+        allocated_bytes: files.iter().map(|file| file.allocated).sum(),
+        reclaimable_bytes: 0,
+        keeper: organized_group.keeper.clone(),
+        action,
+        skipped_during_review: organized_group.untouched,
+        folder: (action == Some(DuplicateAction::Move)).then(|| organized_group.folder.clone()),
+        files,
+    };
+    group.reclaimable_bytes = group
+        .redundant_files()
+        .filter(|file| file.links <= 1)
+        .map(|file| file.allocated)
+        .sum();
+    group
+}
+
+// `-` writes to stdout
+pub fn write_output(destination: &Path, content: &str) -> Result<(), io::Error> {
+    if destination == Path::new("-") {
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(content.as_bytes())?;
+        stdout.flush()
+    } else {
+        fs::write(destination, content)
+    }
+}
@@ -0,0 +1,146 @@
+use chrono::{DateTime, Local};
+use dialoguer::{Confirm, Select};
+use duplicate_finder_rs::duplicate_detector::DuplicateGroup;
+use duplicate_finder_rs::file_scanner;
+use duplicate_finder_rs::hasher::Hash;
+use duplicate_finder_rs::keeper::KeepPolicy;
+use duplicate_finder_rs::organizer::{DuplicateAction, GroupDecision};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// `None` stands for skipping the group
+const CHOICES: [(Option<DuplicateAction>, &str); 6] = [
+    (
+        Some(DuplicateAction::Move),
+        "Move the other copies into the duplicates folder",
+    ),
+    (Some(DuplicateAction::Delete), "Delete the other copies"),
+    (
+        Some(DuplicateAction::Hardlink),
+        "Replace the other copies with hardlinks",
+    ),
+    (
+        Some(DuplicateAction::Symlink),
+        "Replace the other copies with symlinks",
+    ),
+    (
+        Some(DuplicateAction::Reflink),
+        "Share extents with the other copies (reflink)",
+    ),
+    (None, "Skip this group"),
+];
+
+// Steps through every group asking which copy to keep and what to do with the others. A choice
+// can be applied to all remaining groups, which then keep the copy `keep` selects. Returns
+// `None` when the review is abandoned with Esc.
+pub fn review_groups(
+    duplicates: &BTreeMap<Hash, DuplicateGroup>,
+    default_action: DuplicateAction,
+    keep: &KeepPolicy,
+) -> Result<Option<BTreeMap<Hash, GroupDecision>>, io::Error> {
+    let mut decisions = BTreeMap::new();
+    let mut choice_for_remaining = None;
+    let default_choice = CHOICES
+        .iter()
+        .position(|(action, _)| *action == Some(default_action))
+        .unwrap_or(0);
+
+    for (index, (hash, group)) in duplicates.iter().enumerate() {
+        let candidates: Vec<PathBuf> = group
+            .files
+            .iter()
+            .filter(|file| !file_scanner::is_symlink(file))
+            .cloned()
+            .collect();
+        let default_keeper = keep
+            .select(&candidates)?
+            .and_then(|keeper| group.files.iter().position(|file| file == keeper))
+            .unwrap_or(0);
+
+        if let Some(choice) = choice_for_remaining {
+            decisions.insert(hash.clone(), decision(choice, group, default_keeper));
+            continue;
+        }
+
+        // Prompts are drawn on stderr; keep the headers with them so stdout stays clean
+        eprintln!();
+        eprintln!("Group {} of {} ({hash}):", index + 1, duplicates.len());
+        let files: Vec<String> = group.files.iter().map(|file| describe_file(file)).collect();
+
+        let Some(keeper) = Select::new()
+            .with_prompt("Which copy should be kept? (Esc cancels the review)")
+            .items(&files)
+            .default(default_keeper)
+            .interact_opt()
+            .map_err(io::Error::other)?
+        else {
+            return Ok(None);
+        };
+
+        let Some(choice) = Select::new()
+            .with_prompt("What should happen to the other copies?")
+            .items(CHOICES.iter().map(|(_, label)| label))
+            .default(default_choice)
+            .interact_opt()
+            .map_err(io::Error::other)?
+        else {
+            return Ok(None);
+        };
+        let choice = CHOICES[choice].0;
+
+        if index + 1 < duplicates.len() {
+            let Some(apply_to_remaining) = Confirm::new()
+                .with_prompt("Do the same for all remaining groups?")
+                .default(false)
+                .interact_opt()
+                .map_err(io::Error::other)?
+            else {
+                return Ok(None);
+            };
+            if apply_to_remaining {
+                choice_for_remaining = Some(choice);
+            }
+        }
+
+        decisions.insert(hash.clone(), decision(choice, group, keeper));
+    }
+
+    Ok(Some(decisions))
+}
+
+fn decision(
+    choice: Option<DuplicateAction>,
+    group: &DuplicateGroup,
+    keeper: usize,
+) -> GroupDecision {
+    match choice {
+        Some(action) => GroupDecision::Apply {
+            action,
+            keeper: group.files[keeper].clone(),
+        },
+        None => GroupDecision::Skip,
+    }
+}
+
+fn describe_file(path: &Path) -> String {
+    match fs::metadata(path) {
+        Ok(metadata) => {
+            let modified = metadata
+                .modified()
+                .map(|modified| {
+                    DateTime::<Local>::from(modified)
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string()
+                })
+                .unwrap_or_else(|_| "unknown".to_string());
+            format!(
+                "{}  ({} bytes, modified {modified})",
+                path.display(),
+                metadata.len()
+            )
+        }
+        Err(error) => format!("{}  ({error})", path.display()),
+    }
+}
@@ -0,0 +1,84 @@
+use crate::cli::{Cli, Command};
+use clap::Parser;
+
+#[test]
+fn test_cli_without_arguments_falls_back_to_interactive() {
+    let cli = Cli::try_parse_from(["duplicate-finder-rs"]).unwrap();
+    assert!(cli.command.is_none());
+}
+
+#[test]
+fn test_cli_parses_subcommand_with_multiple_roots() {
+    let cli = Cli::try_parse_from(["duplicate-finder-rs", "report", "--quiet", "a", "b"]).unwrap();
+
+    match cli.command {
+        Some(Command::Report(args)) => {
+            assert!(args.quiet);
+            assert_eq!(args.root_paths(), vec!["a".to_string(), "b".to_string()]);
+        }
+        other => panic!("unexpected command: {other:?}"),
+    }
+}
+
+#[test]
+fn test_cli_defaults_to_current_directory() {
+    let cli = Cli::try_parse_from(["duplicate-finder-rs", "scan"]).unwrap();
+
+    match cli.command {
+        Some(Command::Scan(args)) => assert_eq!(args.root_paths(), vec![".".to_string()]),
+        other => panic!("unexpected command: {other:?}"),
+    }
+}
+
+#[test]
+fn test_cli_parses_size_bounds() {
+    let cli = Cli::try_parse_from([
+        "duplicate-finder-rs",
+        "scan",
+        "--min-size",
+        "0",
+        "--max-size",
+        "10M",
+    ])
+    .unwrap();
+
+    match cli.command {
+        Some(Command::Scan(args)) => {
+            let options = args.scan_options();
+            assert_eq!(options.min_size, 0);
+            assert_eq!(options.max_size, Some(10 * 1024 * 1024));
+        }
+        other => panic!("unexpected command: {other:?}"),
+    }
+
+    assert!(Cli::try_parse_from(["duplicate-finder-rs", "scan", "--min-size", "4X"]).is_err());
+}
+
+#[test]
+fn test_cli_rejects_several_reports_on_stdout() {
+    let parse = |args: &[&str]| Cli::try_parse_from(args).unwrap().validate();
+
+    assert!(
+        parse(&[
+            "duplicate-finder-rs",
+            "scan",
+            "--json",
+            "-",
+            "--csv",
+            "out.csv"
+        ])
+        .is_ok()
+    );
+    assert!(parse(&["duplicate-finder-rs", "scan", "--json", "-", "--csv", "-"]).is_err());
+    assert!(
+        parse(&[
+            "duplicate-finder-rs",
+            "organize",
+            "--html",
+            "-",
+            "--markdown",
+            "-"
+        ])
+        .is_err()
+    );
+}
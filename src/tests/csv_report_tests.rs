@@ -0,0 +1,66 @@
+use super::{cleanup_test_files, create_test_files};
+use crate::csv_report::CsvRenderer;
+use crate::keeper::KeepPolicy;
+use crate::report::Renderer;
+use crate::workflow::{self, WorkflowOptions};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn test_csv_report_quotes_odd_file_names() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = "test_csv_report";
+    cleanup_test_files(temp_dir)?;
+    create_test_files(temp_dir)?;
+    let odd_name = PathBuf::from(temp_dir).join("we,ird \"name\".txt");
+    fs::write(&odd_name, "test content 1")?;
+
+    let options = WorkflowOptions {
+        dry_run: true,
+        keep: Some(KeepPolicy::ShortestPath),
+        ..WorkflowOptions::default()
+    };
+    let summary = workflow::execute_with_options(temp_dir, &options)?;
+    let mut report = summary.report;
+
+    let files_csv = CsvRenderer::Files.render(&report)?;
+    let summary_csv = CsvRenderer::Groups.render(&report)?;
+
+    let rows: Vec<&str> = files_csv.split_terminator("\r\n").collect();
+    assert_eq!(
+        rows[0],
+        "group_id,hash,size,allocated_bytes,links,path,modified_utc,is_keeper,planned_action,destination"
+    );
+    assert_eq!(rows.len(), 1 + 5);
+    assert!(files_csv.contains("\"test_csv_report/we,ird \"\"name\"\".txt\""));
+    assert_eq!(
+        rows.iter()
+            .filter(|row| row.contains(",true,keep,"))
+            .count(),
+        2
+    );
+    assert_eq!(
+        rows.iter()
+            .filter(|row| row.contains(",false,move,"))
+            .count(),
+        3
+    );
+
+    let summary_rows: Vec<&str> = summary_csv.split_terminator("\r\n").collect();
+    assert_eq!(summary_rows.len(), 1 + 2);
+    assert!(summary_rows[0].starts_with("group_id,hash,algorithm,size,file_count"));
+    assert!(
+        summary_rows[1..]
+            .iter()
+            .any(|row| row.contains(",sha256,14,3,28,42,"))
+    );
+
+    // Names that a spreadsheet would evaluate as formulas are kept as text
+    report.groups[0].files[0].path = PathBuf::from("=HYPERLINK(\"x\")");
+    report.groups[0].files[1].path = PathBuf::from("@sum.txt");
+    let files_csv = CsvRenderer::Files.render(&report)?;
+    assert!(files_csv.contains(",\"'=HYPERLINK(\"\"x\"\")\","));
+    assert!(files_csv.contains(",\"'@sum.txt\","));
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
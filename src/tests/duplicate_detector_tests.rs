@@ -1,5 +1,5 @@
 use super::{cleanup_test_files, create_test_files};
-use crate::duplicate_detector::{DuplicateDetector, DuplicateGroup};
+use crate::duplicate_detector::{DetectionOptions, DuplicateDetector, DuplicateGroup};
 use crate::file_scanner::FileInfo;
 use std::fs;
 use std::path::PathBuf;
@@ -75,3 +75,168 @@ fn test_duplicate_detector_with_duplicates() -> Result<(), std::io::Error> {
     cleanup_test_files(temp_dir)?;
     Ok(())
 }
+
+#[test]
+fn test_duplicate_detector_skips_files_with_unique_size() -> Result<(), std::io::Error> {
+    let temp_dir = "test_size_prefilter";
+    cleanup_test_files(temp_dir)?;
+    let test_files = create_test_files(temp_dir)?;
+
+    let mut file_infos: Vec<FileInfo> = test_files
+        .into_iter()
+        .map(|path| FileInfo::new(path).unwrap())
+        .collect();
+
+    // A unique size means the file is never opened, so an unreadable path is harmless
+    file_infos.push(FileInfo {
+        path: PathBuf::from(temp_dir).join("missing.bin"),
+        size: 4096,
+        ..FileInfo::default()
+    });
+
+    let duplicates = DuplicateDetector::find_duplicates(file_infos)?;
+    assert_eq!(duplicates.len(), 2);
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_duplicate_detector_reports_stage_statistics() -> Result<(), std::io::Error> {
+    let temp_dir = "test_detection_stages";
+    cleanup_test_files(temp_dir)?;
+    fs::create_dir_all(temp_dir)?;
+
+    let same = vec![0u8; 8192];
+    let mut head_differs = same.clone();
+    head_differs[0] = 1;
+    let mut middle_differs = same.clone();
+    middle_differs[4096] = 1;
+
+    // `middle.bin` passes the partial stage and is only told apart by the full hash
+    let contents = [
+        ("a.bin", same.clone()),
+        ("b.bin", same),
+        ("head.bin", head_differs),
+        ("middle.bin", middle_differs),
+        ("short.bin", vec![0u8; 10]),
+    ];
+
+    let mut file_infos = Vec::new();
+    for (name, content) in contents {
+        let path = PathBuf::from(temp_dir).join(name);
+        fs::write(&path, content)?;
+        file_infos.push(FileInfo::new(path)?);
+    }
+
+    let options = DetectionOptions {
+        partial_block_size: 1024,
+        partial_tail: true,
+        ..DetectionOptions::default()
+    };
+    let detection = DuplicateDetector::find_duplicates_with_options(file_infos, &options)?;
+
+    assert_eq!(detection.groups.len(), 1);
+    assert_eq!(detection.stats.size.candidates, 5);
+    assert_eq!(detection.stats.size.eliminated, 1);
+    assert_eq!(detection.stats.partial.candidates, 4);
+    assert_eq!(detection.stats.partial.eliminated, 1);
+    assert_eq!(detection.stats.full.candidates, 3);
+    assert_eq!(detection.stats.full.eliminated, 1);
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_duplicate_detector_output_is_independent_of_thread_count() -> Result<(), std::io::Error> {
+    let temp_dir = "test_detection_threads";
+    cleanup_test_files(temp_dir)?;
+    fs::create_dir_all(temp_dir)?;
+
+    let mut paths = Vec::new();
+    for i in 0..40 {
+        let path = PathBuf::from(temp_dir).join(format!("file{i:02}.txt"));
+        fs::write(&path, format!("content {}", i % 7))?;
+        paths.push(path);
+    }
+
+    let detect = |threads| -> Result<Vec<(String, Vec<PathBuf>)>, std::io::Error> {
+        let file_infos = paths
+            .iter()
+            .map(|path| FileInfo::new(path.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let options = DetectionOptions {
+            threads,
+            ..DetectionOptions::default()
+        };
+        let detection = DuplicateDetector::find_duplicates_with_options(file_infos, &options)?;
+        Ok(detection
+            .groups
+            .into_iter()
+            .map(|(hash, group)| (hash, group.files))
+            .collect())
+    };
+
+    let sequential = detect(1)?;
+    assert_eq!(sequential.len(), 7);
+    assert_eq!(sequential, detect(8)?);
+
+    // Files keep their scan order inside each group
+    for (_hash, files) in &sequential {
+        assert!(files.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_duplicate_detector_treats_hardlinks_as_one_file() -> Result<(), std::io::Error> {
+    let temp_dir = "test_hardlinks_collapsed";
+    cleanup_test_files(temp_dir)?;
+    fs::create_dir_all(temp_dir)?;
+
+    let root = PathBuf::from(temp_dir);
+    fs::write(root.join("original.txt"), "shared content")?;
+    fs::hard_link(root.join("original.txt"), root.join("link.txt"))?;
+
+    let file_infos = ["original.txt", "link.txt"]
+        .iter()
+        .map(|name| FileInfo::new(root.join(name)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let detection =
+        DuplicateDetector::find_duplicates_with_options(file_infos, &DetectionOptions::default())?;
+    assert!(detection.groups.is_empty());
+    assert_eq!(detection.stats.linked_paths, 1);
+
+    // A real copy still groups with the first path of the linked pair
+    fs::write(root.join("copy.txt"), "shared content")?;
+    let file_infos = ["original.txt", "link.txt", "copy.txt"]
+        .iter()
+        .map(|name| FileInfo::new(root.join(name)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let duplicates = DuplicateDetector::find_duplicates(file_infos)?;
+    let group = duplicates.values().next().unwrap();
+    assert_eq!(
+        group.files,
+        vec![root.join("original.txt"), root.join("copy.txt")]
+    );
+
+    // A followed symlink scanned first gives way to the real path of the same file
+    std::os::unix::fs::symlink("original.txt", root.join("alias.txt"))?;
+    let file_infos = ["alias.txt", "original.txt", "copy.txt"]
+        .iter()
+        .map(|name| FileInfo::new(root.join(name)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let duplicates = DuplicateDetector::find_duplicates(file_infos)?;
+    let group = duplicates.values().next().unwrap();
+    assert_eq!(
+        group.files,
+        vec![root.join("original.txt"), root.join("copy.txt")]
+    );
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use super::cleanup_test_files;
-use crate::file_scanner::{FileInfo, FileScanner};
+use crate::file_scanner::{FileInfo, FileScanner, ScanOptions};
 use std::fs;
 use std::path::PathBuf;
 
@@ -18,6 +18,240 @@ fn test_file_info_creation() -> Result<(), std::io::Error> {
 
     let file_info = FileInfo::new(test_file.clone())?;
     assert_eq!(file_info.path, test_file);
+    assert_eq!(file_info.size, 12);
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_scanner_skips_own_output() -> Result<(), std::io::Error> {
+    let temp_dir = "test_scanner_own_output";
+    cleanup_test_files(temp_dir)?;
+
+    let root = PathBuf::from(temp_dir);
+    let organized = root.join("duplicates").join("abc_file.txt");
+    let nested_project = root.join("project");
+    let user_duplicates = root.join("photos").join("duplicates");
+    fs::create_dir_all(&organized)?;
+    fs::create_dir_all(nested_project.join("duplicates"))?;
+    fs::create_dir_all(&user_duplicates)?;
+
+    fs::write(organized.join("file.txt"), "organized")?;
+    fs::write(root.join("duplicate_files_index.txt"), "index")?;
+    fs::write(root.join("duplicate_files_journal.jsonl"), "")?;
+    fs::write(root.join("duplicate_files_cache.json"), "{}")?;
+    fs::write(nested_project.join("duplicate_files_index.txt"), "index")?;
+    fs::write(nested_project.join("duplicates").join("old.txt"), "old")?;
+    fs::write(user_duplicates.join("photo.jpg"), "photo")?;
+    fs::write(root.join("duplicate_files_notes.txt"), "user file")?;
+    // Deleted duplicates land here when the root is a mount point
+    fs::create_dir_all(root.join(".Trash-1000/files"))?;
+    fs::write(root.join(".Trash-1000/files/file.txt"), "trashed")?;
+
+    let mut scanned: Vec<PathBuf> = FileScanner::new(temp_dir)
+        .scan_files()?
+        .into_iter()
+        .map(|file_info| file_info.path)
+        .collect();
+    scanned.sort();
+
+    assert_eq!(
+        scanned,
+        vec![
+            root.join("duplicate_files_notes.txt"),
+            user_duplicates.join("photo.jpg"),
+        ]
+    );
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_scanner_applies_filters_during_traversal() -> Result<(), std::io::Error> {
+    let temp_dir = "test_scanner_filters";
+    cleanup_test_files(temp_dir)?;
+
+    let root = PathBuf::from(temp_dir);
+    for dir in ["node_modules/pkg", "src", ".git/objects"] {
+        fs::create_dir_all(root.join(dir))?;
+    }
+    fs::write(root.join("node_modules/pkg/index.js"), "module")?;
+    fs::write(root.join(".git/objects/ab"), "object")?;
+    fs::write(root.join("src/main.rs"), "fn main() {}")?;
+    fs::write(root.join("src/notes.txt"), "notes")?;
+
+    let options = ScanOptions {
+        include: vec!["*.rs".to_string(), "*.js".to_string()],
+        exclude: vec!["node_modules/".to_string()],
+        exclude_regex: vec![r"^\.git$".to_string()],
+        ..ScanOptions::default()
+    };
+    let scanned: Vec<PathBuf> = FileScanner::with_options(temp_dir, &options)?
+        .scan_files()?
+        .into_iter()
+        .map(|file_info| file_info.path)
+        .collect();
+
+    assert_eq!(scanned, vec![root.join("src/main.rs")]);
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_scanner_honours_ignore_files() -> Result<(), std::io::Error> {
+    let temp_dir = "test_scanner_ignore_files";
+    cleanup_test_files(temp_dir)?;
+
+    let root = PathBuf::from(temp_dir);
+    for dir in ["app/build", "app/src", "scratch"] {
+        fs::create_dir_all(root.join(dir))?;
+    }
+    fs::write(root.join(".gitignore"), "*.log\n")?;
+    fs::write(root.join("app/.gitignore"), "build/\n!keep.log\n")?;
+    fs::write(root.join(".dupignore"), "scratch/\n")?;
+    fs::write(root.join("app/build/out.bin"), "output")?;
+    fs::write(root.join("app/src/lib.rs"), "pub fn f() {}")?;
+    fs::write(root.join("app/debug.log"), "debug")?;
+    fs::write(root.join("app/keep.log"), "keep")?;
+    fs::write(root.join("scratch/copy.txt"), "copy")?;
+
+    let scan = |options: &ScanOptions| -> Result<Vec<PathBuf>, std::io::Error> {
+        let mut scanned: Vec<PathBuf> = FileScanner::with_options(temp_dir, options)?
+            .scan_files()?
+            .into_iter()
+            .map(|file_info| file_info.path)
+            .filter(|path| !path.ends_with(".gitignore") && !path.ends_with(".dupignore"))
+            .collect();
+        scanned.sort();
+        Ok(scanned)
+    };
+
+    // .dupignore applies on its own; VCS ignore files only when asked for
+    assert_eq!(
+        scan(&ScanOptions::default())?,
+        vec![
+            root.join("app/build/out.bin"),
+            root.join("app/debug.log"),
+            root.join("app/keep.log"),
+            root.join("app/src/lib.rs"),
+        ]
+    );
+
+    let options = ScanOptions {
+        respect_ignore_files: true,
+        ..ScanOptions::default()
+    };
+    assert_eq!(
+        scan(&options)?,
+        vec![root.join("app/keep.log"), root.join("app/src/lib.rs")]
+    );
+
+    // .dupignore can be switched off while VCS ignore files still apply
+    let options = ScanOptions {
+        respect_ignore_files: true,
+        respect_dupignore: false,
+        ..ScanOptions::default()
+    };
+    assert_eq!(
+        scan(&options)?,
+        vec![
+            root.join("app/keep.log"),
+            root.join("app/src/lib.rs"),
+            root.join("scratch/copy.txt"),
+        ]
+    );
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_scanner_applies_size_bounds() -> Result<(), std::io::Error> {
+    let temp_dir = "test_scanner_size_bounds";
+    cleanup_test_files(temp_dir)?;
+
+    let root = PathBuf::from(temp_dir);
+    fs::create_dir_all(root.join("pkg"))?;
+    fs::write(root.join("pkg/__init__.py"), "")?;
+    fs::write(root.join("small.txt"), "tiny")?;
+    fs::write(root.join("large.bin"), vec![0u8; 2048])?;
+
+    let scan = |options: &ScanOptions| -> Result<Vec<PathBuf>, std::io::Error> {
+        let mut scanned: Vec<PathBuf> = FileScanner::with_options(temp_dir, options)?
+            .scan_files()?
+            .into_iter()
+            .map(|file_info| file_info.path)
+            .collect();
+        scanned.sort();
+        Ok(scanned)
+    };
+
+    assert_eq!(
+        scan(&ScanOptions::default())?,
+        vec![root.join("large.bin"), root.join("small.txt")]
+    );
+
+    let options = ScanOptions {
+        min_size: 0,
+        max_size: Some(1024),
+        ..ScanOptions::default()
+    };
+    assert_eq!(
+        scan(&options)?,
+        vec![root.join("pkg/__init__.py"), root.join("small.txt")]
+    );
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_scanner_symlink_policy() -> Result<(), std::io::Error> {
+    use std::os::unix::fs::symlink;
+
+    let temp_dir = "test_scanner_symlinks";
+    cleanup_test_files(temp_dir)?;
+
+    let root = PathBuf::from(temp_dir);
+    fs::create_dir_all(root.join("data/nested"))?;
+    fs::write(root.join("data/file.txt"), "content")?;
+    symlink("file.txt", root.join("data/alias.txt"))?;
+    // Points back at an ancestor; following it must not recurse forever
+    symlink("..", root.join("data/nested/loop"))?;
+
+    let scan = |options: &ScanOptions| -> Result<Vec<PathBuf>, std::io::Error> {
+        let mut scanned: Vec<PathBuf> = FileScanner::with_options(temp_dir, options)?
+            .scan_files()?
+            .into_iter()
+            .map(|file_info| file_info.path)
+            .collect();
+        scanned.sort();
+        Ok(scanned)
+    };
+
+    assert_eq!(
+        scan(&ScanOptions::default())?,
+        vec![root.join("data/file.txt")]
+    );
+
+    let options = ScanOptions {
+        follow_symlinks: true,
+        ..ScanOptions::default()
+    };
+    assert_eq!(
+        scan(&options)?,
+        vec![root.join("data/alias.txt"), root.join("data/file.txt")]
+    );
+    assert_eq!(
+        FileScanner::with_options(temp_dir, &options)?
+            .scan()?
+            .link_loops,
+        1
+    );
 
     cleanup_test_files(temp_dir)?;
     Ok(())
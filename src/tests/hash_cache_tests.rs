@@ -0,0 +1,107 @@
+use super::{cleanup_test_files, create_test_files};
+use crate::duplicate_detector::{DetectionOptions, DuplicateDetector};
+use crate::file_scanner::FileInfo;
+use crate::hash_cache::HashCache;
+use crate::hasher::HashAlgorithm;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn file_infos(paths: &[PathBuf]) -> Vec<FileInfo> {
+    paths
+        .iter()
+        .map(|path| FileInfo::new(path.clone()).unwrap())
+        .collect()
+}
+
+fn cache_path(temp_dir: &str) -> PathBuf {
+    HashCache::default_path(Path::new(temp_dir))
+}
+
+#[test]
+fn test_cache_reuses_hashes_across_runs() -> Result<(), std::io::Error> {
+    let temp_dir = "test_cache_reuse";
+    cleanup_test_files(temp_dir)?;
+    // Leave out `unique.txt`: it is ruled out by the partial hash, so it never gets a full hash
+    let mut paths = create_test_files(temp_dir)?;
+    paths.truncate(4);
+    let options = DetectionOptions::default();
+
+    let mut cache = HashCache::load(cache_path(temp_dir))?;
+    let first =
+        DuplicateDetector::find_duplicates_with_cache(file_infos(&paths), &options, &mut cache)?;
+    assert_eq!(first.stats.cache_hits, 0);
+    cache.save()?;
+
+    let mut cache = HashCache::load(cache_path(temp_dir))?;
+    assert_eq!(cache.len(), 4);
+    let second =
+        DuplicateDetector::find_duplicates_with_cache(file_infos(&paths), &options, &mut cache)?;
+    assert_eq!(second.stats.cache_hits, 4);
+    // Every file of the size group is cached, so the partial stage is skipped entirely
+    assert_eq!(second.stats.partial.candidates, 0);
+
+    let first_groups: Vec<_> = first
+        .groups
+        .into_iter()
+        .map(|(h, g)| (h, g.files))
+        .collect();
+    let second_groups: Vec<_> = second
+        .groups
+        .into_iter()
+        .map(|(h, g)| (h, g.files))
+        .collect();
+    assert_eq!(first_groups, second_groups);
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_cache_ignores_changed_files_and_other_algorithms() -> Result<(), std::io::Error> {
+    let temp_dir = "test_cache_invalidation";
+    cleanup_test_files(temp_dir)?;
+    let paths = create_test_files(temp_dir)?;
+
+    let mut cache = HashCache::load(cache_path(temp_dir))?;
+    let file_info = FileInfo::new(paths[0].clone())?;
+    cache.insert(&file_info, HashAlgorithm::Sha256, "cached".to_string());
+
+    assert_eq!(
+        cache.lookup(&file_info, HashAlgorithm::Sha256),
+        Some(&"cached".to_string())
+    );
+    assert_eq!(cache.lookup(&file_info, HashAlgorithm::Blake3), None);
+
+    fs::write(&paths[0], "changed content, changed size")?;
+    let changed = FileInfo::new(paths[0].clone())?;
+    assert_eq!(cache.lookup(&changed, HashAlgorithm::Sha256), None);
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_cache_prune_and_clear() -> Result<(), std::io::Error> {
+    let temp_dir = "test_cache_prune";
+    cleanup_test_files(temp_dir)?;
+    let paths = create_test_files(temp_dir)?;
+
+    let mut cache = HashCache::load(cache_path(temp_dir))?;
+    for file_info in file_infos(&paths) {
+        cache.insert(&file_info, HashAlgorithm::Sha256, "hash".to_string());
+    }
+    cache.save()?;
+
+    fs::remove_file(&paths[0])?;
+    let mut cache = HashCache::load(cache_path(temp_dir))?;
+    assert_eq!(cache.prune(), 1);
+    assert_eq!(cache.len(), 4);
+    cache.save()?;
+
+    assert!(HashCache::clear(cache_path(temp_dir))?);
+    assert!(!cache_path(temp_dir).exists());
+    assert!(!HashCache::clear(cache_path(temp_dir))?);
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use super::cleanup_test_files;
-use crate::hasher;
+use crate::hasher::{self, HashAlgorithm};
 use std::fs;
 use std::path::PathBuf;
 
@@ -11,7 +11,7 @@ fn test_hasher_compute_hash() -> Result<(), std::io::Error> {
     let content = "test content for hashing";
     fs::write(&test_file, content)?;
 
-    let hash = hasher::compute_file_hash(&test_file)?;
+    let hash = hasher::compute_file_hash(&test_file, HashAlgorithm::Sha256)?;
 
     assert_eq!(hash.len(), 64);
     assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
@@ -32,8 +32,8 @@ fn test_identical_files_have_same_hash() -> Result<(), std::io::Error> {
     fs::write(&file1, content)?;
     fs::write(&file2, content)?;
 
-    let hash1 = hasher::compute_file_hash(&file1)?;
-    let hash2 = hasher::compute_file_hash(&file2)?;
+    let hash1 = hasher::compute_file_hash(&file1, HashAlgorithm::Sha256)?;
+    let hash2 = hasher::compute_file_hash(&file2, HashAlgorithm::Sha256)?;
 
     assert_eq!(hash1, hash2);
 
@@ -52,8 +52,8 @@ fn test_different_files_have_different_hashes() -> Result<(), std::io::Error> {
     fs::write(&file1, "content 1")?;
     fs::write(&file2, "content 2")?;
 
-    let hash1 = hasher::compute_file_hash(&file1)?;
-    let hash2 = hasher::compute_file_hash(&file2)?;
+    let hash1 = hasher::compute_file_hash(&file1, HashAlgorithm::Sha256)?;
+    let hash2 = hasher::compute_file_hash(&file2, HashAlgorithm::Sha256)?;
 
     assert_ne!(hash1, hash2);
 
@@ -69,7 +69,7 @@ fn test_empty_file_hash() -> Result<(), std::io::Error> {
     let empty_file = PathBuf::from(temp_dir).join("empty.txt");
     fs::write(&empty_file, "")?;
 
-    let hash = hasher::compute_file_hash(&empty_file)?;
+    let hash = hasher::compute_file_hash(&empty_file, HashAlgorithm::Sha256)?;
 
     assert_eq!(
         hash,
@@ -79,3 +79,67 @@ fn test_empty_file_hash() -> Result<(), std::io::Error> {
     cleanup_test_files(temp_dir)?;
     Ok(())
 }
+
+#[test]
+fn test_partial_hash_samples_head_and_tail() -> Result<(), std::io::Error> {
+    let temp_dir = "test_partial_hash";
+    fs::create_dir_all(temp_dir)?;
+
+    let mut middle_differs = vec![b'a'; 64];
+    let base = middle_differs.clone();
+    middle_differs[32] = b'b';
+    let mut tail_differs = base.clone();
+    tail_differs[63] = b'b';
+
+    let base_file = PathBuf::from(temp_dir).join("base.bin");
+    let middle_file = PathBuf::from(temp_dir).join("middle.bin");
+    let tail_file = PathBuf::from(temp_dir).join("tail.bin");
+    fs::write(&base_file, &base)?;
+    fs::write(&middle_file, &middle_differs)?;
+    fs::write(&tail_file, &tail_differs)?;
+
+    let base_hash = hasher::compute_partial_hash(&base_file, 16, true, HashAlgorithm::Sha256)?;
+    assert_eq!(
+        base_hash,
+        hasher::compute_partial_hash(&middle_file, 16, true, HashAlgorithm::Sha256)?
+    );
+    assert_ne!(
+        base_hash,
+        hasher::compute_partial_hash(&tail_file, 16, true, HashAlgorithm::Sha256)?
+    );
+    assert_eq!(
+        hasher::compute_partial_hash(&base_file, 16, false, HashAlgorithm::Sha256)?,
+        hasher::compute_partial_hash(&tail_file, 16, false, HashAlgorithm::Sha256)?
+    );
+
+    // A file that fits inside the sampled range is hashed exactly like the full hash
+    assert_eq!(
+        hasher::compute_partial_hash(&base_file, 128, true, HashAlgorithm::Sha256)?,
+        hasher::compute_file_hash(&base_file, HashAlgorithm::Sha256)?
+    );
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_hash_algorithms_produce_distinct_digests() -> Result<(), std::io::Error> {
+    let temp_dir = "test_hash_algorithms";
+    fs::create_dir_all(temp_dir)?;
+    let test_file = PathBuf::from(temp_dir).join("algorithms.txt");
+    fs::write(&test_file, "")?;
+
+    let sha256 = hasher::compute_file_hash(&test_file, HashAlgorithm::Sha256)?;
+    let blake3 = hasher::compute_file_hash(&test_file, HashAlgorithm::Blake3)?;
+    let xxh3 = hasher::compute_file_hash(&test_file, HashAlgorithm::Xxh3)?;
+
+    assert_eq!(
+        blake3,
+        "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+    );
+    assert_eq!(xxh3, "99aa06d3014798d86001c324468d497f");
+    assert_ne!(sha256, blake3);
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
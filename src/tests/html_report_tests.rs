@@ -0,0 +1,41 @@
+use super::{cleanup_test_files, create_test_files};
+use crate::html_report::HtmlRenderer;
+use crate::report::Renderer;
+use crate::workflow::{self, WorkflowMode, WorkflowOptions};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn test_html_report_is_self_contained_and_escaped() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = "test_html_report";
+    cleanup_test_files(temp_dir)?;
+    create_test_files(temp_dir)?;
+    fs::write(
+        PathBuf::from(temp_dir).join("<b>&copy.txt"),
+        "test content 2",
+    )?;
+
+    let options = WorkflowOptions {
+        mode: WorkflowMode::Report,
+        ..WorkflowOptions::default()
+    };
+    let summary = workflow::execute_with_options(temp_dir, &options)?;
+    let report = summary.report;
+
+    let html = HtmlRenderer.render(&report)?;
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert_eq!(html.matches("<details ").count(), 2);
+    for group in &report.groups {
+        assert!(html.contains(&format!("data-wasted=\"{}\"", group.reclaimable_bytes)));
+    }
+    assert!(html.contains("Wasted space by directory"));
+    assert!(html.contains("&lt;b&gt;&amp;copy.txt"));
+    assert!(!html.contains("<b>&copy"));
+    // Nothing is loaded from elsewhere
+    assert!(!html.contains("src="));
+    assert!(!html.contains("href="));
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
@@ -0,0 +1,203 @@
+use super::{cleanup_test_files, create_test_files};
+use crate::duplicate_detector::DuplicateDetector;
+use crate::file_scanner::FileInfo;
+use crate::hasher::HashAlgorithm;
+use crate::journal::{self, ConflictReason};
+use crate::organizer::{DeleteMode, DuplicateAction, OrganizeOptions, Organizer};
+use std::fs;
+use std::path::PathBuf;
+
+fn organize(temp_dir: &str) -> Result<Vec<PathBuf>, std::io::Error> {
+    cleanup_test_files(temp_dir)?;
+    let test_files = create_test_files(temp_dir)?;
+
+    let file_infos = test_files
+        .iter()
+        .map(|path| FileInfo::new(path.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let duplicates = DuplicateDetector::find_duplicates(file_infos)?;
+    Organizer::new(temp_dir).organize_duplicates(duplicates)?;
+
+    Ok(test_files)
+}
+
+#[test]
+fn test_organizer_journals_every_move() -> Result<(), std::io::Error> {
+    let temp_dir = "test_journal_records_moves";
+    organize(temp_dir)?;
+
+    let entries = journal::read_journal(temp_dir)?;
+    assert_eq!(entries.len(), 4);
+    for entry in &entries {
+        assert!(entry.original_path.is_absolute());
+        assert!(!entry.original_path.exists());
+        assert!(entry.new_path.exists());
+        assert_eq!(entry.algorithm, HashAlgorithm::Sha256);
+    }
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_restore_moves_files_back() -> Result<(), std::io::Error> {
+    let temp_dir = "test_journal_restore";
+    let test_files = organize(temp_dir)?;
+
+    let summary = journal::restore(temp_dir)?;
+    assert_eq!(summary.restored.len(), 4);
+    assert!(summary.conflicts.is_empty());
+    assert!(test_files.iter().all(|path| path.exists()));
+    assert!(!journal::journal_path(temp_dir).exists());
+    assert!(!PathBuf::from(temp_dir).join("duplicates").exists());
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_restore_reports_conflicts_and_keeps_them_in_journal() -> Result<(), std::io::Error> {
+    let temp_dir = "test_journal_conflicts";
+    let test_files = organize(temp_dir)?;
+
+    let entries = journal::read_journal(temp_dir)?;
+    let occupied = entries[0].original_path.clone();
+    let modified = entries[1].new_path.clone();
+    fs::write(&occupied, "someone else's file")?;
+    fs::write(&modified, "edited after organizing")?;
+
+    let summary = journal::restore(temp_dir)?;
+    assert_eq!(summary.restored.len(), 2);
+    assert_eq!(summary.conflicts.len(), 2);
+    assert!(
+        summary
+            .conflicts
+            .iter()
+            .any(|conflict| matches!(conflict.reason, ConflictReason::OriginalOccupied))
+    );
+    assert!(
+        summary
+            .conflicts
+            .iter()
+            .any(|conflict| matches!(conflict.reason, ConflictReason::HashMismatch { .. }))
+    );
+    assert_eq!(fs::read_to_string(&occupied)?, "someone else's file");
+
+    let remaining = journal::read_journal(temp_dir)?;
+    assert_eq!(remaining, vec![entries[0].clone(), entries[1].clone()]);
+    assert_eq!(test_files.iter().filter(|path| path.exists()).count(), 4);
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_restore_gives_hardlinked_paths_their_own_copy() -> Result<(), std::io::Error> {
+    use std::os::unix::fs::MetadataExt;
+
+    let temp_dir = "test_journal_restore_hardlinks";
+    cleanup_test_files(temp_dir)?;
+    let test_files = create_test_files(temp_dir)?;
+
+    let file_infos = test_files
+        .iter()
+        .map(|path| FileInfo::new(path.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let duplicates = DuplicateDetector::find_duplicates(file_infos)?;
+    Organizer::new(temp_dir)
+        .with_options(OrganizeOptions {
+            action: DuplicateAction::Hardlink,
+            ..OrganizeOptions::default()
+        })
+        .organize_duplicates(duplicates)?;
+
+    let entries = journal::read_journal(temp_dir)?;
+    assert_eq!(entries.len(), 2);
+    assert!(
+        entries
+            .iter()
+            .all(|entry| entry.action == DuplicateAction::Hardlink)
+    );
+
+    let summary = journal::restore(temp_dir)?;
+    assert_eq!(summary.restored.len(), 2);
+    assert!(summary.conflicts.is_empty());
+    for entry in &entries {
+        assert_ne!(
+            fs::metadata(&entry.original_path)?.ino(),
+            fs::metadata(&entry.new_path)?.ino()
+        );
+        assert_eq!(fs::read(&entry.original_path)?, fs::read(&entry.new_path)?);
+    }
+    assert!(!journal::journal_path(temp_dir).exists());
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_restore_replaces_symlinks_with_copies() -> Result<(), std::io::Error> {
+    let temp_dir = "test_journal_restore_symlinks";
+    cleanup_test_files(temp_dir)?;
+    let test_files = create_test_files(temp_dir)?;
+
+    let file_infos = test_files
+        .iter()
+        .map(|path| FileInfo::new(path.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let duplicates = DuplicateDetector::find_duplicates(file_infos)?;
+    Organizer::new(temp_dir)
+        .with_options(OrganizeOptions {
+            action: DuplicateAction::Symlink,
+            ..OrganizeOptions::default()
+        })
+        .organize_duplicates(duplicates)?;
+
+    let entries = journal::read_journal(temp_dir)?;
+    assert_eq!(entries.len(), 2);
+
+    let summary = journal::restore(temp_dir)?;
+    assert_eq!(summary.restored.len(), 2);
+    for entry in &entries {
+        let metadata = fs::symlink_metadata(&entry.original_path)?;
+        assert!(metadata.file_type().is_file());
+        assert_eq!(fs::read(&entry.original_path)?, fs::read(&entry.new_path)?);
+    }
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_restore_takes_deleted_files_out_of_quarantine() -> Result<(), std::io::Error> {
+    let temp_dir = "test_journal_restore_deleted";
+    let quarantine = "test_journal_restore_deleted_quarantine";
+    cleanup_test_files(temp_dir)?;
+    cleanup_test_files(quarantine)?;
+    let test_files = create_test_files(temp_dir)?;
+
+    let file_infos = test_files
+        .iter()
+        .map(|path| FileInfo::new(path.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let duplicates = DuplicateDetector::find_duplicates(file_infos)?;
+    Organizer::new(temp_dir)
+        .with_options(OrganizeOptions {
+            action: DuplicateAction::Delete,
+            delete_mode: DeleteMode::Quarantine(PathBuf::from(quarantine)),
+            ..OrganizeOptions::default()
+        })
+        .organize_duplicates(duplicates)?;
+    assert_eq!(test_files.iter().filter(|path| path.exists()).count(), 3);
+
+    let summary = journal::restore(temp_dir)?;
+    assert_eq!(summary.restored.len(), 2);
+    assert!(test_files.iter().all(|path| path.exists()));
+    assert_eq!(fs::read_to_string(&test_files[3])?, "test content 2");
+
+    cleanup_test_files(temp_dir)?;
+    cleanup_test_files(quarantine)?;
+    Ok(())
+}
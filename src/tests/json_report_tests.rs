@@ -0,0 +1,98 @@
+use super::{cleanup_test_files, create_test_files};
+use crate::json_report::JsonRenderer;
+use crate::report::{REPORT_SCHEMA_VERSION, Renderer, Report};
+use crate::workflow::{self, WorkflowMode, WorkflowOptions};
+
+#[test]
+fn test_json_report_lists_groups_and_totals() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = "test_json_report";
+    cleanup_test_files(temp_dir)?;
+    let test_files = create_test_files(temp_dir)?;
+
+    let options = WorkflowOptions {
+        dry_run: true,
+        ..WorkflowOptions::default()
+    };
+    let summary = workflow::execute_with_options(temp_dir, &options)?;
+    let report = summary.report;
+
+    let json: serde_json::Value = serde_json::from_str(&JsonRenderer.render(&report)?)?;
+
+    assert_eq!(json["schema_version"], REPORT_SCHEMA_VERSION);
+    assert_eq!(json["run"]["mode"], "organize");
+    assert_eq!(json["run"]["dry_run"], true);
+    assert_eq!(json["run"]["action"], "move");
+    assert_eq!(json["run"]["roots"][0], temp_dir);
+
+    // Two groups of two 14-byte files; without a keep policy every copy is moved
+    assert_eq!(json["totals"]["files_scanned"], 5);
+    assert_eq!(json["totals"]["duplicate_groups"], 2);
+    assert_eq!(json["totals"]["duplicate_files"], 4);
+    assert_eq!(json["totals"]["redundant_files"], 2);
+    assert_eq!(json["totals"]["redundant_bytes"], 28);
+    assert_eq!(json["totals"]["files_acted_on"], 4);
+
+    let groups = json["groups"].as_array().expect("groups array");
+    let group = groups
+        .iter()
+        .find(|group| {
+            group["files"].as_array().is_some_and(|files| {
+                files
+                    .iter()
+                    .any(|file| file["path"] == test_files[0].to_str().unwrap())
+            })
+        })
+        .expect("group containing file1");
+    assert_eq!(group["size"], 14);
+    assert_eq!(group["action"], "move");
+    assert!(group["keeper"].is_null());
+    assert!(group["files"][0]["modified"].is_string());
+    assert!(
+        group["files"][1]["destination"]
+            .as_str()
+            .is_some_and(|destination| destination.contains("duplicates"))
+    );
+    assert!(test_files.iter().all(|path| path.exists()));
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_json_report_has_no_action_outside_organize() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = "test_json_report_scan";
+    cleanup_test_files(temp_dir)?;
+    create_test_files(temp_dir)?;
+
+    let options = WorkflowOptions {
+        mode: WorkflowMode::Scan,
+        ..WorkflowOptions::default()
+    };
+    let summary = workflow::execute_with_options(temp_dir, &options)?;
+    let report = summary.report;
+    let json = serde_json::to_value(&report)?;
+
+    assert_eq!(json["run"]["mode"], "scan");
+    assert!(json["run"]["action"].is_null());
+    assert_eq!(json["totals"]["files_acted_on"], 0);
+    for group in json["groups"].as_array().expect("groups array") {
+        assert!(group["action"].is_null());
+        assert!(group["files"][0]["destination"].is_null());
+    }
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_json_report_without_results_still_lists_roots() -> Result<(), Box<dyn std::error::Error>> {
+    let roots = vec!["first".to_string(), "second".to_string()];
+    let report = Report::combine(Vec::new(), &WorkflowOptions::default(), &roots);
+
+    let json: serde_json::Value = serde_json::from_str(&JsonRenderer.render(&report)?)?;
+    assert_eq!(json["run"]["roots"], serde_json::json!(["first", "second"]));
+    assert_eq!(json["totals"]["duplicate_groups"], 0);
+    assert_eq!(json["groups"], serde_json::json!([]));
+
+    Ok(())
+}
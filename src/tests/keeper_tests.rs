@@ -0,0 +1,65 @@
+use super::cleanup_test_files;
+use crate::keeper::KeepPolicy;
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+fn create_files(temp_dir: &str) -> Result<Vec<PathBuf>, std::io::Error> {
+    cleanup_test_files(temp_dir)?;
+    let paths = vec![
+        PathBuf::from(temp_dir).join("archive").join("photo.jpg"),
+        PathBuf::from(temp_dir).join("p.jpg"),
+        PathBuf::from(temp_dir).join("projects").join("photo.jpg"),
+    ];
+
+    let now = SystemTime::now();
+    for (i, path) in paths.iter().enumerate() {
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, b"same")?;
+        // The middle file is the oldest, the last one the newest
+        let offset = Duration::from_secs(match i {
+            0 => 200,
+            1 => 300,
+            _ => 100,
+        });
+        File::options()
+            .write(true)
+            .open(path)?
+            .set_modified(now - offset)?;
+    }
+
+    Ok(paths)
+}
+
+#[test]
+fn test_keep_policy_rules() -> Result<(), std::io::Error> {
+    let temp_dir = "test_keep_policy_rules";
+    let paths = create_files(temp_dir)?;
+
+    assert_eq!(KeepPolicy::FirstScanned.select(&paths)?, Some(&paths[0]));
+    assert_eq!(KeepPolicy::OldestModified.select(&paths)?, Some(&paths[1]));
+    assert_eq!(KeepPolicy::NewestModified.select(&paths)?, Some(&paths[2]));
+    assert_eq!(KeepPolicy::ShortestPath.select(&paths)?, Some(&paths[1]));
+
+    let priority = KeepPolicy::Priority(vec![
+        PathBuf::from(temp_dir).join("projects"),
+        PathBuf::from(temp_dir).join("archive"),
+    ]);
+    assert_eq!(priority.select(&paths)?, Some(&paths[2]));
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_keep_policy_priority_falls_back_to_scan_order() -> Result<(), std::io::Error> {
+    let temp_dir = "test_keep_policy_fallback";
+    let paths = create_files(temp_dir)?;
+
+    let priority = KeepPolicy::Priority(vec![PathBuf::from(temp_dir).join("missing")]);
+    assert_eq!(priority.select(&paths)?, Some(&paths[0]));
+    assert_eq!(KeepPolicy::FirstScanned.select(&[])?, None);
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
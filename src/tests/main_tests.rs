@@ -0,0 +1,10 @@
+use crate::handle_workflow_error;
+use duplicate_finder_rs::workflow::WorkflowError;
+
+#[test]
+fn test_handle_workflow_error_displays_messages() {
+    let error = std::io::Error::other("inner error");
+    let workflow_error = WorkflowError::Scan { source: error };
+
+    handle_workflow_error(&workflow_error);
+}
@@ -0,0 +1,38 @@
+use super::{cleanup_test_files, create_test_files};
+use crate::markdown_report::MarkdownRenderer;
+use crate::report::Renderer;
+use crate::workflow::{self, WorkflowOptions};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn test_markdown_report_escapes_table_cells() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = "test_markdown_report";
+    cleanup_test_files(temp_dir)?;
+    create_test_files(temp_dir)?;
+    fs::write(PathBuf::from(temp_dir).join("a|b_c.txt"), "unique content")?;
+
+    let options = WorkflowOptions {
+        dry_run: true,
+        ..WorkflowOptions::default()
+    };
+    let summary = workflow::execute_with_options(temp_dir, &options)?;
+    let markdown = MarkdownRenderer.render(&summary.report)?;
+
+    assert!(markdown.starts_with("# Duplicate files report"));
+    assert!(markdown.contains("| Action | move (dry run) |"));
+    assert_eq!(markdown.matches("\n## Group ").count(), 3);
+    assert!(markdown.contains("a\\|b\\_c.txt"));
+    // Every row of a file table has exactly the three columns
+    let rows: Vec<&str> = markdown
+        .lines()
+        .filter(|line| line.starts_with("| test\\_markdown\\_report/"))
+        .collect();
+    assert_eq!(rows.len(), 6);
+    for row in rows {
+        assert_eq!(row.matches(" | ").count(), 2, "{row}");
+    }
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
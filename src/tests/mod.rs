@@ -2,8 +2,21 @@ mod utils;
 
 pub use utils::{cleanup_test_files, create_test_files};
 
+mod cli_tests;
+mod csv_report_tests;
 mod duplicate_detector_tests;
 mod file_scanner_tests;
+mod hash_cache_tests;
 mod hasher_tests;
+mod html_report_tests;
+mod journal_tests;
+mod json_report_tests;
+mod keeper_tests;
+mod markdown_report_tests;
 mod organizer_tests;
+mod path_filter_tests;
+// Hardlink counts are only reported on Unix
+#[cfg(unix)]
+mod report_tests;
+mod verifier_tests;
 mod workflow_tests;
@@ -1,10 +1,23 @@
-use super::cleanup_test_files;
+use super::{cleanup_test_files, create_test_files};
 use crate::duplicate_detector::{DuplicateDetector, DuplicateGroup};
 use crate::file_scanner::FileInfo;
-use crate::organizer::{OrganizedGroup, Organizer};
+use crate::keeper::KeepPolicy;
+use crate::organizer::{
+    DeleteMode, DuplicateAction, GroupDecision, OrganizeOptions, OrganizedGroup, Organizer,
+    SymlinkStyle,
+};
+use crate::report::{Renderer, Report};
+use crate::text_report::TextRenderer;
+use crate::workflow::WorkflowOptions;
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
+// Renders the text index the way a default organize run writes it
+fn render_index(root: &str, groups: &[OrganizedGroup]) -> Result<String, std::io::Error> {
+    TextRenderer.render(&Report::build(root, &WorkflowOptions::default(), 0, groups))
+}
+
 #[test]
 fn test_organizer_creation() {
     let organizer = Organizer::new("/tmp");
@@ -14,9 +27,6 @@ fn test_organizer_creation() {
 #[test]
 fn test_comprehensive_index_creation() -> Result<(), std::io::Error> {
     let temp_dir = "test_comprehensive_index";
-    fs::create_dir_all(temp_dir)?;
-
-    let organizer = Organizer::new(temp_dir);
 
     let hash1 = "hash1_1234567890abcdef".to_string();
     let hash2 = "hash2_0987654321fedcba".to_string();
@@ -31,34 +41,29 @@ fn test_comprehensive_index_creation() -> Result<(), std::io::Error> {
         .join("duplicates")
         .join(format!("{hash2}_file3.txt"));
 
-    fs::create_dir_all(&folder1)?;
-    fs::create_dir_all(&folder2)?;
-
     let organized_groups = vec![
         OrganizedGroup {
             hash: hash1.clone(),
             group: group1,
             folder: folder1.clone(),
-            file_sizes: vec![0],
+            file_infos: vec![FileInfo::default()],
+            ..OrganizedGroup::default()
         },
         OrganizedGroup {
             hash: hash2.clone(),
             group: group2,
             folder: folder2.clone(),
-            file_sizes: vec![0],
+            file_infos: vec![FileInfo::default()],
+            ..OrganizedGroup::default()
         },
     ];
 
-    organizer.create_comprehensive_index(&organized_groups)?;
-
-    let index_path = PathBuf::from(temp_dir).join("duplicate_files_index.txt");
-    assert!(index_path.exists());
-
-    let index_content = fs::read_to_string(&index_path)?;
+    let index_content = render_index(temp_dir, &organized_groups)?;
     assert!(index_content.contains("Duplicate Files Comprehensive Index"));
     assert!(index_content.contains("Total duplicate groups found: 2"));
     assert!(index_content.contains(&hash1));
     assert!(index_content.contains(&hash2));
+    assert!(index_content.contains(&format!("Folder: {}", folder1.display())));
 
     cleanup_test_files(temp_dir)?;
     Ok(())
@@ -142,16 +147,494 @@ fn test_comprehensive_index_reports_freed_space() -> Result<(), std::io::Error>
         .map(|path| FileInfo::new(path.clone()))
         .collect::<Result<Vec<_>, _>>()?;
 
+    // Without a keeper every copy but the first is redundant
+    let reclaimable: u64 = file_infos[1..]
+        .iter()
+        .map(|file_info| file_info.allocated)
+        .sum();
+
     let duplicates = DuplicateDetector::find_duplicates(file_infos)?;
     assert_eq!(duplicates.len(), 1);
 
     let organized_groups = organizer.organize_duplicates(duplicates)?;
-    organizer.create_comprehensive_index(&organized_groups)?;
+    let index_content = render_index(temp_dir, &organized_groups)?;
+    assert!(index_content.contains("Redundant copies: 2 (2.00 MB logical)"));
+    assert!(index_content.contains(&format!(
+        "Space that can be freed: {:.2} MB on disk",
+        reclaimable as f64 / (1024.0 * 1024.0)
+    )));
+    assert!(index_content.contains(&format!("bytes allocated, {reclaimable} bytes reclaimable")));
+    assert!(index_content.contains("Space: 3145728 bytes logical, "));
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_dry_run_plans_unique_destinations_without_moving() -> Result<(), std::io::Error> {
+    let temp_dir = "test_dry_run_unique_destinations";
+    cleanup_test_files(temp_dir)?;
+
+    let paths = [
+        PathBuf::from(temp_dir).join("a").join("same.bin"),
+        PathBuf::from(temp_dir).join("b").join("same.bin"),
+    ];
+    for path in &paths {
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, b"planned")?;
+    }
+
+    let file_infos = paths
+        .iter()
+        .map(|path| FileInfo::new(path.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let duplicates = DuplicateDetector::find_duplicates(file_infos)?;
+
+    let organizer = Organizer::new(temp_dir).with_options(OrganizeOptions {
+        dry_run: true,
+        ..OrganizeOptions::default()
+    });
+    let organized_groups = organizer.organize_duplicates(duplicates)?;
+
+    let moves = &organized_groups[0].moves;
+    assert_eq!(moves.len(), 2);
+    assert_ne!(moves[0].destination, moves[1].destination);
+    assert!(paths.iter().all(|path| path.exists()));
+    assert!(!organized_groups[0].folder.exists());
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_organizer_leaves_keeper_in_place() -> Result<(), std::io::Error> {
+    let temp_dir = "test_organizer_keeper";
+    cleanup_test_files(temp_dir)?;
+
+    let keeper = PathBuf::from(temp_dir).join("k.txt");
+    let long_copy = PathBuf::from(temp_dir).join("nested").join("copy.txt");
+    fs::create_dir_all(long_copy.parent().unwrap())?;
+    fs::write(&long_copy, b"keep me")?;
+    fs::write(&keeper, b"keep me")?;
+
+    let file_infos = vec![
+        FileInfo::new(long_copy.clone())?,
+        FileInfo::new(keeper.clone())?,
+    ];
+    let duplicates = DuplicateDetector::find_duplicates(file_infos)?;
+
+    let organizer = Organizer::new(temp_dir).with_options(OrganizeOptions {
+        keep: Some(KeepPolicy::ShortestPath),
+        ..OrganizeOptions::default()
+    });
+    let organized_groups = organizer.organize_duplicates(duplicates)?;
+
+    assert_eq!(organized_groups[0].keeper.as_ref(), Some(&keeper));
+    assert_eq!(organized_groups[0].moves.len(), 1);
+    assert_eq!(organized_groups[0].moves[0].source, long_copy);
+    assert!(keeper.exists());
+    assert!(!long_copy.exists());
+
+    let index_content = render_index(temp_dir, &organized_groups)?;
+    assert!(index_content.contains(&format!("Kept in place: {}", keeper.display())));
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_comprehensive_index_labels_empty_files() -> Result<(), std::io::Error> {
+    let temp_dir = "test_index_empty_files";
+    cleanup_test_files(temp_dir)?;
+    fs::create_dir_all(temp_dir)?;
+
+    let paths: Vec<PathBuf> = ["a/__init__.py", "b/__init__.py"]
+        .iter()
+        .map(|name| PathBuf::from(temp_dir).join(name))
+        .collect();
+    for path in &paths {
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, "")?;
+    }
+
+    let file_infos = paths
+        .iter()
+        .map(|path| FileInfo::new(path.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let duplicates = DuplicateDetector::find_duplicates(file_infos)?;
+
+    let organizer = Organizer::new(temp_dir);
+    let described_groups = organizer.describe_duplicates(duplicates)?;
+    assert!(described_groups[0].is_empty_files());
+    let index_content = render_index(temp_dir, &described_groups)?;
+    assert!(index_content.contains("Category: Empty files (0 bytes)"));
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_organizer_replaces_duplicates_with_hardlinks() -> Result<(), std::io::Error> {
+    use std::os::unix::fs::MetadataExt;
+
+    let temp_dir = "test_organizer_hardlinks";
+    cleanup_test_files(temp_dir)?;
+    let test_files = create_test_files(temp_dir)?;
+
+    let file_infos = test_files
+        .iter()
+        .map(|path| FileInfo::new(path.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let duplicates = DuplicateDetector::find_duplicates(file_infos)?;
+
+    let organizer = Organizer::new(temp_dir).with_options(OrganizeOptions {
+        action: DuplicateAction::Hardlink,
+        ..OrganizeOptions::default()
+    });
+    let organized_groups = organizer.organize_duplicates(duplicates)?;
+    assert_eq!(organized_groups.len(), 2);
+
+    for organized_group in &organized_groups {
+        let keeper = organized_group.keeper.as_ref().unwrap();
+        assert_eq!(keeper, &organized_group.group.files[0]);
+        assert_eq!(organized_group.moves.len(), 1);
+
+        let linked = &organized_group.moves[0].source;
+        assert_eq!(&organized_group.moves[0].destination, keeper);
+        assert_eq!(fs::metadata(linked)?.ino(), fs::metadata(keeper)?.ino());
+    }
+    assert!(!PathBuf::from(temp_dir).join("duplicates").exists());
+
+    let index_content = render_index(temp_dir, &organized_groups)?;
+    assert!(index_content.contains("Action: hardlink"));
+    assert!(index_content.contains("  Hardlinks:"));
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_organizer_replaces_duplicates_with_symlinks() -> Result<(), std::io::Error> {
+    let temp_dir = "test_organizer_symlinks";
+    cleanup_test_files(temp_dir)?;
+
+    let root = PathBuf::from(temp_dir);
+    let keeper = root.join("keep.txt");
+    let relative_copy = root.join("nested").join("relative.txt");
+    let absolute_copy = root.join("nested").join("absolute.txt");
+    fs::create_dir_all(root.join("nested"))?;
+    for path in [&keeper, &relative_copy, &absolute_copy] {
+        fs::write(path, "linked content")?;
+    }
+
+    for (style, copy) in [
+        (SymlinkStyle::Relative, &relative_copy),
+        (SymlinkStyle::Absolute, &absolute_copy),
+    ] {
+        let file_infos = vec![FileInfo::new(keeper.clone())?, FileInfo::new(copy.clone())?];
+        let duplicates = DuplicateDetector::find_duplicates(file_infos)?;
+        Organizer::new(temp_dir)
+            .with_options(OrganizeOptions {
+                action: DuplicateAction::Symlink,
+                symlink_style: style,
+                ..OrganizeOptions::default()
+            })
+            .organize_duplicates(duplicates)?;
+
+        assert!(fs::symlink_metadata(copy)?.file_type().is_symlink());
+        assert_eq!(fs::read_to_string(copy)?, "linked content");
+    }
+
+    assert_eq!(
+        fs::read_link(&relative_copy)?,
+        PathBuf::from("..").join("keep.txt")
+    );
+    assert_eq!(fs::read_link(&absolute_copy)?, fs::canonicalize(&keeper)?);
+    assert!(!fs::symlink_metadata(&keeper)?.file_type().is_symlink());
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_organizer_reflink_shares_extents_or_skips() -> Result<(), std::io::Error> {
+    let temp_dir = "test_organizer_reflink";
+    cleanup_test_files(temp_dir)?;
+    let test_files = create_test_files(temp_dir)?;
+
+    let file_infos = test_files
+        .iter()
+        .map(|path| FileInfo::new(path.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let duplicates = DuplicateDetector::find_duplicates(file_infos)?;
+
+    let organizer = Organizer::new(temp_dir).with_options(OrganizeOptions {
+        action: DuplicateAction::Reflink,
+        ..OrganizeOptions::default()
+    });
+    let organized_groups = organizer.organize_duplicates(duplicates)?;
+
+    // Whether extents can be shared depends on the filesystem running the tests; either way
+    // every copy is accounted for and stays a regular file with its original content
+    for organized_group in &organized_groups {
+        assert_eq!(
+            organized_group.moves.len() + organized_group.skipped.len(),
+            1
+        );
+    }
+    for path in &test_files {
+        assert!(fs::symlink_metadata(path)?.file_type().is_file());
+    }
+    assert_eq!(fs::read_to_string(&test_files[1])?, "test content 1");
+    assert!(!PathBuf::from(temp_dir).join("duplicates").exists());
+    assert!(
+        !PathBuf::from(temp_dir)
+            .join("duplicate_files_journal.jsonl")
+            .exists()
+    );
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+// Needs a copy-on-write filesystem, e.g. an XFS or Btrfs loopback mount:
+// DUPLICATE_FINDER_REFLINK_DIR=/mnt/reflink cargo test -- --ignored reflink
+#[cfg(target_os = "linux")]
+#[test]
+#[ignore]
+fn test_organizer_reflink_shares_extents_on_cow_filesystem() -> Result<(), std::io::Error> {
+    let base = std::env::var_os("DUPLICATE_FINDER_REFLINK_DIR")
+        .map(PathBuf::from)
+        .expect("DUPLICATE_FINDER_REFLINK_DIR must point at an XFS or Btrfs mount");
+    let temp_dir = base.join("test_organizer_reflink_cow");
+    if temp_dir.exists() {
+        fs::remove_dir_all(&temp_dir)?;
+    }
+    fs::create_dir_all(&temp_dir)?;
+
+    // Larger than one 16 MiB dedupe call, with a tail that is not block aligned
+    let payload: Vec<u8> = (0..40 * 1024 * 1024 + 123)
+        .map(|index: usize| (index % 251) as u8)
+        .collect();
+    let paths = [temp_dir.join("keep.bin"), temp_dir.join("copy.bin")];
+    for path in &paths {
+        fs::write(path, &payload)?;
+    }
+
+    let file_infos = paths
+        .iter()
+        .map(|path| FileInfo::new(path.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let duplicates = DuplicateDetector::find_duplicates(file_infos)?;
+    let organized_groups = Organizer::new(&temp_dir)
+        .with_options(OrganizeOptions {
+            action: DuplicateAction::Reflink,
+            ..OrganizeOptions::default()
+        })
+        .organize_duplicates(duplicates)?;
+
+    assert_eq!(organized_groups.len(), 1);
+    assert_eq!(organized_groups[0].skipped, Vec::new());
+    assert_eq!(organized_groups[0].moves.len(), 1);
+    assert_eq!(fs::read(&paths[1])?, payload);
+
+    fs::remove_dir_all(&temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_organizer_deletes_into_quarantine_or_permanently() -> Result<(), std::io::Error> {
+    let temp_dir = "test_organizer_delete";
+    cleanup_test_files(temp_dir)?;
+    let test_files = create_test_files(temp_dir)?;
+    let quarantine = PathBuf::from("test_organizer_delete_quarantine");
+    cleanup_test_files("test_organizer_delete_quarantine")?;
+
+    let detect = || -> Result<_, std::io::Error> {
+        let file_infos = test_files
+            .iter()
+            .filter(|path| path.exists())
+            .map(|path| FileInfo::new(path.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        DuplicateDetector::find_duplicates(file_infos)
+    };
+
+    let organized_groups = Organizer::new(temp_dir)
+        .with_options(OrganizeOptions {
+            action: DuplicateAction::Delete,
+            delete_mode: DeleteMode::Quarantine(quarantine.clone()),
+            ..OrganizeOptions::default()
+        })
+        .organize_duplicates(detect()?)?;
+
+    assert_eq!(organized_groups.len(), 2);
+    assert!(test_files[0].exists() && !test_files[1].exists());
+    assert!(test_files[2].exists() && !test_files[3].exists());
+    assert_eq!(
+        fs::read_to_string(quarantine.join("file2.txt"))?,
+        "test content 1"
+    );
+    assert!(
+        organized_groups
+            .iter()
+            .any(|group| group.moves[0].destination == quarantine.join("file2.txt"))
+    );
+
+    // Put a copy back and remove it for good this time
+    fs::write(&test_files[1], "test content 1")?;
+    let organized_groups = Organizer::new(temp_dir)
+        .with_options(OrganizeOptions {
+            action: DuplicateAction::Delete,
+            delete_mode: DeleteMode::Permanent,
+            ..OrganizeOptions::default()
+        })
+        .organize_duplicates(detect()?)?;
+
+    assert_eq!(organized_groups[0].moves[0].destination, PathBuf::new());
+    assert!(!test_files[1].exists());
+    assert!(!quarantine.join("file2_copy1.txt").exists());
+
+    cleanup_test_files(temp_dir)?;
+    cleanup_test_files("test_organizer_delete_quarantine")?;
+    Ok(())
+}
+
+#[test]
+fn test_organizer_follows_per_group_decisions() -> Result<(), std::io::Error> {
+    let temp_dir = "test_organizer_decisions";
+    cleanup_test_files(temp_dir)?;
+    let test_files = create_test_files(temp_dir)?;
+    let quarantine = PathBuf::from("test_organizer_decisions_quarantine");
+    cleanup_test_files("test_organizer_decisions_quarantine")?;
+
+    let file_infos = test_files
+        .iter()
+        .map(|path| FileInfo::new(path.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let duplicates = DuplicateDetector::find_duplicates(file_infos)?;
+
+    // Keep the second copy of one group and delete the first; leave the other group alone
+    let mut decisions = BTreeMap::new();
+    for (hash, group) in &duplicates {
+        let decision = if group.files.contains(&test_files[0]) {
+            GroupDecision::Apply {
+                action: DuplicateAction::Delete,
+                keeper: test_files[1].clone(),
+            }
+        } else {
+            GroupDecision::Skip
+        };
+        decisions.insert(hash.clone(), decision);
+    }
+
+    let organizer = Organizer::new(temp_dir).with_options(OrganizeOptions {
+        delete_mode: DeleteMode::Quarantine(quarantine.clone()),
+        decisions,
+        ..OrganizeOptions::default()
+    });
+    let organized_groups = organizer.organize_duplicates(duplicates)?;
+    let index_content = render_index(temp_dir, &organized_groups)?;
+
+    assert!(!test_files[0].exists() && test_files[1].exists());
+    assert!(test_files[2].exists() && test_files[3].exists());
+    assert!(quarantine.join("file1.txt").exists());
+    assert!(!PathBuf::from(temp_dir).join("duplicates").exists());
+
+    let skipped = organized_groups
+        .iter()
+        .find(|group| group.untouched)
+        .expect("skipped group is still reported");
+    assert!(skipped.moves.is_empty());
+
+    assert!(index_content.contains("Action: skipped during review"));
+    assert!(index_content.contains("Action: delete"));
+
+    cleanup_test_files(temp_dir)?;
+    cleanup_test_files("test_organizer_decisions_quarantine")?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_organizer_never_acts_through_followed_symlinks() -> Result<(), std::io::Error> {
+    use crate::file_scanner::{FileScanner, ScanOptions};
+    use std::os::unix::fs::symlink;
+
+    let temp_dir = "test_organizer_followed_symlinks";
+    cleanup_test_files(temp_dir)?;
+
+    // The link is shorter than the real copy, so `ShortestPath` would pick it
+    let base = PathBuf::from(temp_dir);
+    let root = base.join("root");
+    fs::create_dir_all(base.join("outside"))?;
+    fs::create_dir_all(root.join("a"))?;
+    fs::create_dir_all(root.join("bbbbbbbb/c"))?;
+    fs::write(base.join("outside/real.txt"), "linked data")?;
+    let link = root.join("a/l.txt");
+    let copy = root.join("bbbbbbbb/c/copy.txt");
+    symlink("../../outside/real.txt", &link)?;
+    fs::write(&copy, "linked data")?;
+
+    let options = ScanOptions {
+        follow_symlinks: true,
+        ..ScanOptions::default()
+    };
+    let files = FileScanner::with_options(&root, &options)?.scan_files()?;
+    let duplicates = DuplicateDetector::find_duplicates(files)?;
+    assert_eq!(duplicates.len(), 1);
+
+    for (action, keep) in [
+        (DuplicateAction::Hardlink, KeepPolicy::ShortestPath),
+        (DuplicateAction::Hardlink, KeepPolicy::FirstScanned),
+        (DuplicateAction::Delete, KeepPolicy::ShortestPath),
+    ] {
+        let organized_groups = Organizer::new(&root)
+            .with_options(OrganizeOptions {
+                action,
+                keep: Some(keep),
+                delete_mode: DeleteMode::Permanent,
+                ..OrganizeOptions::default()
+            })
+            .organize_duplicates(duplicates.clone())?;
+
+        let group = &organized_groups[0];
+        assert_eq!(group.keeper.as_ref(), Some(&copy));
+        assert!(group.moves.is_empty());
+        assert_eq!(group.skipped.len(), 1);
+        assert_eq!(group.skipped[0].path, link);
+        assert!(fs::symlink_metadata(&link)?.file_type().is_symlink());
+        assert_eq!(fs::read_to_string(&copy)?, "linked data");
+    }
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hardlink_refuses_symlink_target() -> Result<(), std::io::Error> {
+    use crate::linker;
+    use std::os::unix::fs::symlink;
+
+    let temp_dir = "test_hardlink_symlink_target";
+    cleanup_test_files(temp_dir)?;
+
+    // Hardlinking the link itself would leave a relative target that is wrong from `copy`
+    let root = PathBuf::from(temp_dir);
+    fs::create_dir_all(root.join("data"))?;
+    fs::create_dir_all(root.join("a/b"))?;
+    fs::write(root.join("data/real.txt"), "shared data")?;
+    let keeper = root.join("a/b/keep.txt");
+    let copy = root.join("copy.txt");
+    symlink("../../data/real.txt", &keeper)?;
+    fs::write(&copy, "shared data")?;
 
-    let index_path = PathBuf::from(temp_dir).join("duplicate_files_index.txt");
-    assert!(index_path.exists());
-    let index_content = fs::read_to_string(&index_path)?;
-    assert!(index_content.contains("Space that can be freed: Approximately 2.00 MB (estimated)"));
+    assert!(linker::replace_with_hardlink(&keeper, &copy).is_err());
+    assert!(fs::symlink_metadata(&copy)?.file_type().is_file());
+    assert_eq!(fs::read_to_string(&copy)?, "shared data");
+    assert_eq!(fs::read_dir(&root)?.count(), 3);
 
     cleanup_test_files(temp_dir)?;
     Ok(())
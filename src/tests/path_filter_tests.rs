@@ -0,0 +1,63 @@
+use crate::path_filter::PathFilter;
+use std::path::Path;
+
+fn globs(patterns: &[&str]) -> Vec<String> {
+    patterns.iter().map(|pattern| pattern.to_string()).collect()
+}
+
+#[test]
+fn test_exclude_globs_follow_gitignore_rules() {
+    let filter = PathFilter::new(
+        &[],
+        &globs(&["node_modules", "*.tmp", "build/", "/docs/*.pdf"]),
+        &[],
+        &[],
+    )
+    .unwrap();
+
+    assert!(!filter.allows(Path::new("web/node_modules"), true));
+    assert!(!filter.allows(Path::new("a/b/cache.tmp"), false));
+    assert!(!filter.allows(Path::new("src/build"), true));
+    // `build/` only matches directories
+    assert!(filter.allows(Path::new("src/build"), false));
+    assert!(!filter.allows(Path::new("docs/manual.pdf"), false));
+    // Anchored patterns do not match deeper paths
+    assert!(filter.allows(Path::new("other/docs/manual.pdf"), false));
+    assert!(filter.allows(Path::new("src/main.rs"), false));
+}
+
+#[test]
+fn test_include_patterns_only_restrict_files() {
+    let filter = PathFilter::new(&globs(&["*.jpg", "**/raw/*.cr2"]), &[], &[], &[]).unwrap();
+
+    assert!(filter.allows(Path::new("photos"), true));
+    assert!(filter.allows(Path::new("photos/2024/a.jpg"), false));
+    assert!(filter.allows(Path::new("photos/raw/a.cr2"), false));
+    assert!(!filter.allows(Path::new("photos/a.cr2"), false));
+    assert!(!filter.allows(Path::new("notes.txt"), false));
+}
+
+#[test]
+fn test_regex_filters_match_path_or_name() {
+    let filter = PathFilter::new(
+        &[],
+        &[],
+        &globs(&[r"^IMG_\d+\.jpe?g$"]),
+        &globs(&[r"(^|/)\.git(/|$)", r"thumb"]),
+    )
+    .unwrap();
+
+    assert!(filter.allows(Path::new("camera/IMG_0001.jpg"), false));
+    assert!(!filter.allows(Path::new("camera/thumbs/IMG_0001.jpg"), false));
+    assert!(!filter.allows(Path::new("repo/.git"), true));
+    assert!(!filter.allows(Path::new("camera/holiday.jpg"), false));
+}
+
+#[test]
+fn test_invalid_patterns_are_rejected() {
+    let glob_error = PathFilter::new(&[], &globs(&["[unclosed"]), &[], &[]).unwrap_err();
+    assert_eq!(glob_error.kind(), std::io::ErrorKind::InvalidInput);
+
+    let regex_error = PathFilter::new(&[], &[], &[], &globs(&["(unclosed"])).unwrap_err();
+    assert_eq!(regex_error.kind(), std::io::ErrorKind::InvalidInput);
+}
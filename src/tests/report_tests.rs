@@ -0,0 +1,68 @@
+use super::cleanup_test_files;
+use crate::duplicate_detector::DuplicateGroup;
+use crate::file_scanner::FileInfo;
+use crate::organizer::OrganizedGroup;
+use crate::report::Report;
+use crate::workflow::WorkflowOptions;
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn test_report_counts_hardlinked_copies_as_not_reclaimable() -> Result<(), std::io::Error> {
+    let temp_dir = "test_report_reclaimable";
+    cleanup_test_files(temp_dir)?;
+    fs::create_dir_all(temp_dir)?;
+
+    let payload = vec![1u8; 64 * 1024];
+    let single = PathBuf::from(temp_dir).join("single.bin");
+    let linked = PathBuf::from(temp_dir).join("linked.bin");
+    fs::write(&single, &payload)?;
+    fs::write(&linked, &payload)?;
+    // A second name outside the group keeps `linked`'s data alive whatever happens to it
+    fs::hard_link(&linked, PathBuf::from(temp_dir).join("elsewhere.bin"))?;
+
+    let organized_group = |keeper: &PathBuf| -> Result<OrganizedGroup, std::io::Error> {
+        let mut group = DuplicateGroup::new();
+        group.add_file(single.clone());
+        group.add_file(linked.clone());
+        Ok(OrganizedGroup {
+            file_infos: vec![
+                FileInfo::new(single.clone())?,
+                FileInfo::new(linked.clone())?,
+            ],
+            group,
+            keeper: Some(keeper.clone()),
+            ..OrganizedGroup::default()
+        })
+    };
+
+    // Dropping the hardlinked copy frees nothing
+    let report = Report::build(
+        temp_dir,
+        &WorkflowOptions::default(),
+        2,
+        &[organized_group(&single)?],
+    );
+    let group = &report.groups[0];
+    assert_eq!(group.logical_bytes, 2 * 64 * 1024);
+    assert!(group.allocated_bytes > 0);
+    assert_eq!(group.files[1].links, 2);
+    assert_eq!(group.reclaimable_bytes, 0);
+    assert_eq!(report.totals.redundant_bytes, 64 * 1024);
+    assert_eq!(report.totals.reclaimable_bytes, 0);
+
+    // Dropping the unlinked copy frees everything allocated for it
+    let report = Report::build(
+        temp_dir,
+        &WorkflowOptions::default(),
+        2,
+        &[organized_group(&linked)?],
+    );
+    let group = &report.groups[0];
+    assert_eq!(group.reclaimable_bytes, group.files[0].allocated);
+    assert!(group.reclaimable_bytes > 0);
+    assert_eq!(report.totals.reclaimable_bytes, group.reclaimable_bytes);
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use super::cleanup_test_files;
+use crate::duplicate_detector::DuplicateDetector;
+use crate::file_scanner::FileInfo;
+use crate::hasher::{HashAlgorithm, compute_file_hash};
+use crate::verifier::{self, VerificationIssue};
+use std::fs;
+use std::path::PathBuf;
+
+fn write_files(temp_dir: &str, names: &[&str], content: &str) -> Vec<PathBuf> {
+    names
+        .iter()
+        .map(|name| {
+            let path = PathBuf::from(temp_dir).join(name);
+            fs::write(&path, content).unwrap();
+            path
+        })
+        .collect()
+}
+
+#[test]
+fn test_verification_keeps_identical_groups() -> Result<(), std::io::Error> {
+    let temp_dir = "test_verify_identical";
+    cleanup_test_files(temp_dir)?;
+    fs::create_dir_all(temp_dir)?;
+
+    let paths = write_files(temp_dir, &["a.txt", "b.txt", "c.txt"], "same bytes");
+    let file_infos = paths
+        .iter()
+        .map(|path| FileInfo::new(path.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let duplicates = DuplicateDetector::find_duplicates(file_infos)?;
+
+    let verification = verifier::verify_groups(duplicates)?;
+    assert!(verification.issues.is_empty());
+    assert_eq!(verification.groups.len(), 1);
+    assert_eq!(verification.groups.values().next().unwrap().files, paths);
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_verification_splits_files_changed_after_hashing() -> Result<(), std::io::Error> {
+    let temp_dir = "test_verify_changed";
+    cleanup_test_files(temp_dir)?;
+    fs::create_dir_all(temp_dir)?;
+
+    let paths = write_files(temp_dir, &["a.txt", "b.txt", "c.txt", "d.txt"], "original");
+    let file_infos = paths
+        .iter()
+        .map(|path| FileInfo::new(path.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let duplicates = DuplicateDetector::find_duplicates(file_infos)?;
+    let hash = duplicates.keys().next().unwrap().clone();
+
+    fs::write(&paths[0], "modified")?;
+    fs::remove_file(&paths[3])?;
+
+    let verification = verifier::verify_groups(duplicates)?;
+    assert_eq!(
+        verification.groups[&hash].files,
+        vec![paths[1].clone(), paths[2].clone()]
+    );
+    assert_eq!(verification.issues.len(), 2);
+    assert!(
+        verification
+            .issues
+            .contains(&VerificationIssue::ChangedSinceHashing {
+                hash: hash.clone(),
+                file: paths[0].clone(),
+            })
+    );
+    assert!(verification.issues.contains(&VerificationIssue::Missing {
+        hash,
+        file: paths[3].clone(),
+    }));
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_verification_regroups_files_changed_alike() -> Result<(), std::io::Error> {
+    let temp_dir = "test_verify_changed_alike";
+    cleanup_test_files(temp_dir)?;
+    fs::create_dir_all(temp_dir)?;
+
+    let paths = write_files(temp_dir, &["a.txt", "b.txt", "c.txt", "d.txt"], "original");
+    let file_infos = paths
+        .iter()
+        .map(|path| FileInfo::new(path.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let duplicates = DuplicateDetector::find_duplicates(file_infos)?;
+    let hash = duplicates.keys().next().unwrap().clone();
+
+    fs::write(&paths[0], "modified")?;
+    fs::write(&paths[1], "modified")?;
+    let new_hash = compute_file_hash(&paths[0], HashAlgorithm::default())?;
+
+    let verification = verifier::verify_groups(duplicates)?;
+    assert!(verification.issues.is_empty());
+    assert_eq!(verification.groups.len(), 2);
+    assert_eq!(
+        verification.groups[&hash].files,
+        vec![paths[2].clone(), paths[3].clone()]
+    );
+    assert_eq!(
+        verification.groups[&new_hash].files,
+        vec![paths[0].clone(), paths[1].clone()]
+    );
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_files_equal_compares_bytes() -> Result<(), std::io::Error> {
+    let temp_dir = "test_verify_files_equal";
+    cleanup_test_files(temp_dir)?;
+    fs::create_dir_all(temp_dir)?;
+
+    let same = write_files(temp_dir, &["a.bin", "b.bin"], &"x".repeat(200_000));
+    let different = write_files(temp_dir, &["c.bin"], &format!("{}y", "x".repeat(199_999)));
+
+    assert!(verifier::files_equal(&same[0], &same[1])?);
+    assert!(!verifier::files_equal(&same[0], &different[0])?);
+
+    cleanup_test_files(temp_dir)?;
+    Ok(())
+}
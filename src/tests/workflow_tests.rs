@@ -1,5 +1,6 @@
-use crate::handle_workflow_error;
-use crate::workflow::{self, WorkflowError};
+use crate::organizer::{DeleteMode, DuplicateAction, GroupDecision};
+use crate::workflow::{self, WorkflowError, WorkflowMode, WorkflowOptions};
+use std::error::Error;
 use std::fs;
 use std::path::PathBuf;
 
@@ -19,11 +20,15 @@ fn test_workflow_handles_missing_duplicates() {
 }
 
 #[test]
-fn test_handle_workflow_error_displays_messages() {
+fn test_workflow_error_displays_messages() {
     let error = std::io::Error::other("inner error");
     let workflow_error = WorkflowError::Scan { source: error };
 
-    handle_workflow_error(&workflow_error);
+    assert_eq!(
+        workflow_error.to_string(),
+        "Error scanning files: inner error"
+    );
+    assert_eq!(workflow_error.source().unwrap().to_string(), "inner error");
 }
 
 #[test]
@@ -77,3 +82,200 @@ fn test_workflow_skips_current_executable() {
 
     super::cleanup_test_files(temp_dir).unwrap();
 }
+
+#[test]
+fn test_workflow_report_mode_leaves_files_in_place() {
+    let temp_dir = "test_workflow_report_mode";
+    super::cleanup_test_files(temp_dir).unwrap();
+    let test_files = super::create_test_files(temp_dir).unwrap();
+
+    let options = WorkflowOptions {
+        mode: WorkflowMode::Report,
+        ..WorkflowOptions::default()
+    };
+    let summary = workflow::execute_with_options(temp_dir, &options).unwrap();
+
+    assert!(summary.duplicates_found);
+    assert_eq!(summary.duplicate_group_count, 2);
+    assert!(summary.index_content.is_some());
+    assert!(test_files.iter().all(|path| path.exists()));
+    assert!(!PathBuf::from(temp_dir).join("duplicates").exists());
+
+    super::cleanup_test_files(temp_dir).unwrap();
+}
+
+#[test]
+fn test_workflow_scan_mode_writes_nothing() {
+    let temp_dir = "test_workflow_scan_mode";
+    super::cleanup_test_files(temp_dir).unwrap();
+    let test_files = super::create_test_files(temp_dir).unwrap();
+
+    let options = WorkflowOptions {
+        mode: WorkflowMode::Scan,
+        ..WorkflowOptions::default()
+    };
+    let summary = workflow::execute_with_options(temp_dir, &options).unwrap();
+
+    assert_eq!(summary.groups.len(), 2);
+    assert!(summary.index_content.is_none());
+    assert!(!summary.index_path.exists());
+    assert!(test_files.iter().all(|path| path.exists()));
+
+    super::cleanup_test_files(temp_dir).unwrap();
+}
+
+#[test]
+fn test_workflow_dry_run_returns_plan_without_moving() {
+    let temp_dir = "test_workflow_dry_run";
+    super::cleanup_test_files(temp_dir).unwrap();
+    let test_files = super::create_test_files(temp_dir).unwrap();
+
+    let options = WorkflowOptions {
+        dry_run: true,
+        ..WorkflowOptions::default()
+    };
+    let summary = workflow::execute_with_options(temp_dir, &options).unwrap();
+
+    assert_eq!(summary.plan.len(), 4);
+    assert!(test_files.iter().all(|path| path.exists()));
+    assert!(!PathBuf::from(temp_dir).join("duplicates").exists());
+    for planned_move in &summary.plan {
+        assert!(
+            planned_move
+                .destination
+                .starts_with(PathBuf::from(temp_dir).join("duplicates"))
+        );
+        assert!(!planned_move.destination.exists());
+        assert_eq!(planned_move.size, 14);
+    }
+
+    let index_content = summary.index_content.unwrap();
+    assert!(index_content.contains("Mode: Dry run"));
+    assert!(index_content.contains("Planned moves:"));
+
+    super::cleanup_test_files(temp_dir).unwrap();
+}
+
+#[test]
+fn test_workflow_second_run_is_idempotent() {
+    let temp_dir = "test_workflow_idempotent";
+    super::cleanup_test_files(temp_dir).unwrap();
+    super::create_test_files(temp_dir).unwrap();
+
+    let first = workflow::execute(temp_dir).unwrap();
+    assert_eq!(first.duplicate_group_count, 2);
+
+    let second = workflow::execute(temp_dir).unwrap();
+    assert!(!second.duplicates_found);
+    assert_eq!(second.files_scanned, 1);
+
+    let duplicates_dir = PathBuf::from(temp_dir).join("duplicates");
+    assert_eq!(fs::read_dir(&duplicates_dir).unwrap().count(), 2);
+    assert!(!duplicates_dir.join("duplicates").exists());
+
+    super::cleanup_test_files(temp_dir).unwrap();
+}
+
+#[test]
+fn test_workflow_does_not_rescan_quarantine_inside_root() {
+    let temp_dir = "test_workflow_quarantine_inside";
+    super::cleanup_test_files(temp_dir).unwrap();
+    super::create_test_files(temp_dir).unwrap();
+
+    let quarantine = PathBuf::from(temp_dir).join("quarantine");
+    let options = WorkflowOptions {
+        action: DuplicateAction::Delete,
+        delete_mode: DeleteMode::Quarantine(quarantine.clone()),
+        ..WorkflowOptions::default()
+    };
+
+    let first = workflow::execute_with_options(temp_dir, &options).unwrap();
+    assert_eq!(first.duplicate_group_count, 2);
+    // Both copies plus the marker that keeps later scans out
+    assert_eq!(fs::read_dir(&quarantine).unwrap().count(), 3);
+    assert!(quarantine.join(".duplicate_files_quarantine").is_file());
+
+    let second = workflow::execute_with_options(temp_dir, &options).unwrap();
+    assert!(!second.duplicates_found);
+    assert_eq!(second.files_scanned, 3);
+
+    // Runs that do not name the quarantine skip it too
+    let third = workflow::execute_with_options(
+        temp_dir,
+        &WorkflowOptions {
+            mode: WorkflowMode::Scan,
+            ..WorkflowOptions::default()
+        },
+    )
+    .unwrap();
+    assert!(!third.duplicates_found);
+    assert_eq!(third.files_scanned, 3);
+    assert_eq!(fs::read_dir(&quarantine).unwrap().count(), 3);
+
+    super::cleanup_test_files(temp_dir).unwrap();
+}
+
+#[test]
+fn test_workflow_verifies_files_changed_before_resolving() {
+    let temp_dir = "test_workflow_verify_late";
+    super::cleanup_test_files(temp_dir).unwrap();
+    let test_files = super::create_test_files(temp_dir).unwrap();
+
+    let options = WorkflowOptions {
+        verify: true,
+        ..WorkflowOptions::default()
+    };
+    let detected = workflow::detect(temp_dir, &options).unwrap();
+    assert_eq!(detected.duplicates.len(), 2);
+
+    // Edited while a confirmation prompt would be waiting
+    fs::write(&test_files[1], "edited meanwhile").unwrap();
+    let summary = workflow::resolve(temp_dir, &options, detected).unwrap();
+
+    assert_eq!(summary.duplicate_group_count, 1);
+    assert_eq!(summary.verification_issues.len(), 1);
+    assert!(test_files[0].exists() && test_files[1].exists());
+    assert!(!test_files[2].exists() && !test_files[3].exists());
+    super::cleanup_test_files(temp_dir).unwrap();
+
+    // A keeper chosen during review that changes afterwards leaves its group untouched
+    let test_files = super::create_test_files(temp_dir).unwrap();
+    let third_copy = PathBuf::from(temp_dir).join("file1_copy.txt");
+    fs::write(&third_copy, "test content 1").unwrap();
+    let detected = workflow::detect(temp_dir, &options).unwrap();
+    let decisions = detected
+        .duplicates
+        .iter()
+        .map(|(hash, group)| {
+            let keeper = if group.files.contains(&test_files[0]) {
+                test_files[0].clone()
+            } else {
+                group.files[0].clone()
+            };
+            let decision = GroupDecision::Apply {
+                action: DuplicateAction::Delete,
+                keeper,
+            };
+            (hash.clone(), decision)
+        })
+        .collect();
+    let options = WorkflowOptions {
+        decisions,
+        delete_mode: DeleteMode::Permanent,
+        ..options
+    };
+    fs::write(&test_files[0], "edited meanwhile").unwrap();
+    let summary = workflow::resolve(temp_dir, &options, detected).unwrap();
+
+    assert_eq!(summary.plan.len(), 1);
+    assert!(test_files[1].exists() && third_copy.exists());
+    assert_eq!(
+        [&test_files[2], &test_files[3]]
+            .iter()
+            .filter(|path| path.exists())
+            .count(),
+        1
+    );
+
+    super::cleanup_test_files(temp_dir).unwrap();
+}
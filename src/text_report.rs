@@ -0,0 +1,144 @@
+use crate::organizer::DuplicateAction;
+use crate::report::{Renderer, Report};
+use std::io;
+
+// The plain text index written into every reported or organized directory
+pub struct TextRenderer;
+
+impl Renderer for TextRenderer {
+    fn render(&self, report: &Report) -> Result<String, io::Error> {
+        let run = &report.run;
+        let mut index_content = String::new();
+        index_content.push_str("Duplicate Files Comprehensive Index\n");
+        index_content.push_str("===================================\n\n");
+        index_content.push_str(&format!(
+            "Total duplicate groups found: {}\n",
+            report.groups.len()
+        ));
+        index_content.push_str(&format!(
+            "Index created: {}\n",
+            run.generated_at.format("%Y-%m-%d %H:%M:%S UTC")
+        ));
+        index_content.push_str(&format!("Scanned directory: {}\n", run.roots.join(", ")));
+        index_content.push_str(&format!("Hash algorithm: {}\n", run.algorithm));
+        match (run.action, &run.action_details) {
+            (None | Some(DuplicateAction::Move), _) => {}
+            (Some(action), Some(details)) => {
+                index_content.push_str(&format!("Action: {action} ({details})\n"))
+            }
+            (Some(action), None) => index_content.push_str(&format!("Action: {action}\n")),
+        }
+        if run.dry_run {
+            index_content
+                .push_str("Mode: Dry run (no folders were created and no files were moved)\n");
+        }
+        index_content.push('\n');
+
+        let totals = &report.totals;
+        index_content.push_str(&format!(
+            "Total files in duplicate groups: {}\n",
+            totals.duplicate_files
+        ));
+        index_content.push_str(&format!(
+            "Size of duplicate files: {} logical, {} allocated on disk\n",
+            megabytes(totals.logical_bytes),
+            megabytes(totals.allocated_bytes)
+        ));
+        index_content.push_str(&format!(
+            "Redundant copies: {} ({} logical)\n",
+            totals.redundant_files,
+            megabytes(totals.redundant_bytes)
+        ));
+        index_content.push_str(&format!(
+            "Space that can be freed: {} on disk (copies with hardlinks elsewhere free nothing)\n\n",
+            megabytes(totals.reclaimable_bytes)
+        ));
+
+        index_content.push_str("Duplicate Groups:\n");
+        index_content.push_str("================\n\n");
+
+        for (i, group) in report.groups.iter().enumerate() {
+            index_content.push_str(&format!("Group {}:\n", i + 1));
+            if group.is_empty_files() {
+                index_content.push_str("  Category: Empty files (0 bytes)\n");
+            }
+            index_content.push_str(&format!("  Hash: {} ({})\n", group.hash, group.algorithm));
+            if group.skipped_during_review {
+                index_content.push_str("  Action: skipped during review\n");
+            } else if let Some(action) = group.action
+                && group.action != run.action
+            {
+                index_content.push_str(&format!("  Action: {action}\n"));
+            }
+            if let Some(folder) = &group.folder {
+                index_content.push_str(&format!("  Folder: {}\n", folder.display()));
+            }
+            index_content.push_str(&format!("  Files in group: {}\n", group.files.len()));
+            index_content.push_str(&format!(
+                "  Space: {} bytes logical, {} bytes allocated, {} bytes reclaimable\n",
+                group.logical_bytes, group.allocated_bytes, group.reclaimable_bytes
+            ));
+            if let Some(keeper) = &group.keeper {
+                index_content.push_str(&format!("  Kept in place: {}\n", keeper.display()));
+            }
+            index_content.push_str("  File paths:\n");
+
+            for file in &group.files {
+                index_content.push_str(&format!("    - {}\n", file.path.display()));
+            }
+
+            let moved: Vec<_> = group
+                .files
+                .iter()
+                .filter_map(|file| Some((&file.path, file.destination.as_ref()?)))
+                .collect();
+            if let Some(action) = group.action
+                && !moved.is_empty()
+            {
+                let heading = match action {
+                    DuplicateAction::Move => "Moves",
+                    DuplicateAction::Hardlink => "Hardlinks",
+                    DuplicateAction::Symlink => "Symlinks",
+                    DuplicateAction::Reflink => "Shared extents",
+                    DuplicateAction::Delete => "Deletions",
+                };
+                if run.dry_run {
+                    index_content.push_str(&format!("  Planned {}:\n", heading.to_lowercase()));
+                } else {
+                    index_content.push_str(&format!("  {heading}:\n"));
+                }
+                for (source, destination) in moved {
+                    if destination.as_os_str().is_empty() {
+                        index_content.push_str(&format!("    - {}\n", source.display()));
+                    } else {
+                        index_content.push_str(&format!(
+                            "    - {} -> {}\n",
+                            source.display(),
+                            destination.display()
+                        ));
+                    }
+                }
+            }
+
+            let skipped: Vec<_> = group
+                .files
+                .iter()
+                .filter_map(|file| Some((&file.path, file.skipped.as_ref()?)))
+                .collect();
+            if !skipped.is_empty() {
+                index_content.push_str("  Skipped:\n");
+                for (path, reason) in skipped {
+                    index_content.push_str(&format!("    - {} ({reason})\n", path.display()));
+                }
+            }
+
+            index_content.push('\n');
+        }
+
+        Ok(index_content)
+    }
+}
+
+fn megabytes(bytes: u64) -> String {
+    format!("{:.2} MB", bytes as f64 / (1024.0 * 1024.0))
+}
@@ -0,0 +1,279 @@
+use crate::artifacts::QUARANTINE_MARKER_NAME;
+use crate::organizer::unique_destination;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+const TRASH_INFO_EXTENSION: &str = "trashinfo";
+
+// Where deleted duplicates go instead of being unlinked. The home trash follows the
+// freedesktop.org Trash specification (`files/` plus a `.trashinfo` per file in `info/`), so
+// file managers can restore from it; a quarantine folder simply mirrors the scanned tree.
+#[derive(Debug, Clone)]
+pub struct Trash {
+    files_dir: PathBuf,
+    info_dir: Option<PathBuf>,
+}
+
+impl Trash {
+    // `$XDG_DATA_HOME/Trash`, falling back to `~/.local/share/Trash`
+    pub fn home() -> Result<Self, io::Error> {
+        let data_home = env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "no home trash directory (HOME is not set); use a quarantine folder instead",
+                )
+            })?;
+
+        let trash_dir = data_home.join("Trash");
+        Ok(Trash {
+            files_dir: trash_dir.join("files"),
+            info_dir: Some(trash_dir.join("info")),
+        })
+    }
+
+    pub fn quarantine<P: AsRef<Path>>(directory: P) -> Self {
+        Trash {
+            files_dir: directory.as_ref().to_path_buf(),
+            info_dir: None,
+        }
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.files_dir
+    }
+
+    // Moves `path` into the trash and returns where it ended up. In a quarantine folder the
+    // file keeps its path relative to the scanned root, given as `relative_path`.
+    pub fn put(&self, path: &Path, relative_path: &Path) -> Result<PathBuf, io::Error> {
+        let destination = match &self.info_dir {
+            Some(info_dir) if same_volume(path, &self.files_dir) => {
+                reserve_trash_entry(path, &self.files_dir, info_dir)?
+            }
+            Some(_) => {
+                let trash_dir = volume_trash_dir(path)?;
+                reserve_trash_entry(path, &trash_dir.join("files"), &trash_dir.join("info"))?
+            }
+            None => {
+                let destination = self.files_dir.join(relative_path);
+                let directory = destination.parent().unwrap_or(&self.files_dir);
+                fs::create_dir_all(directory)?;
+                self.mark_quarantine()?;
+                let file_name = destination.file_name().unwrap_or(relative_path.as_os_str());
+                unique_destination(directory, file_name, |candidate| {
+                    fs::symlink_metadata(candidate).is_ok()
+                })
+            }
+        };
+
+        if let Err(error) = move_file(path, &destination) {
+            if let Some(info_path) = self.info_path(&destination) {
+                let _ = fs::remove_file(info_path);
+            }
+            return Err(error);
+        }
+
+        Ok(destination)
+    }
+
+    // Drops the `.trashinfo` of a file that was taken back out of the home trash
+    pub fn forget(trashed_path: &Path) {
+        if let Some(info_path) = trash_info_path(trashed_path) {
+            let _ = fs::remove_file(info_path);
+        }
+    }
+
+    fn mark_quarantine(&self) -> Result<(), io::Error> {
+        let marker = self.files_dir.join(QUARANTINE_MARKER_NAME);
+        if marker.is_file() {
+            return Ok(());
+        }
+        fs::write(
+            marker,
+            "Files removed as duplicates by duplicate-finder-rs; scans skip this folder.\n",
+        )
+    }
+
+    fn info_path(&self, trashed_path: &Path) -> Option<PathBuf> {
+        self.info_dir.as_ref()?;
+        trash_info_path(trashed_path)
+    }
+}
+
+// The spec reserves a name by atomically creating its info file first
+fn reserve_trash_entry(
+    path: &Path,
+    files_dir: &Path,
+    info_dir: &Path,
+) -> Result<PathBuf, io::Error> {
+    fs::create_dir_all(files_dir)?;
+    fs::create_dir_all(info_dir)?;
+
+    let original = std::path::absolute(path)?;
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Invalid file name"))?;
+
+    loop {
+        let destination = unique_destination(files_dir, file_name, |candidate| {
+            fs::symlink_metadata(candidate).is_ok()
+                || trash_info_path(candidate).is_some_and(|info| info.exists())
+        });
+        let Some(info_path) = trash_info_path(&destination) else {
+            return Err(io::Error::other("Invalid trash file name"));
+        };
+
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&info_path)
+        {
+            Ok(mut info) => {
+                write!(
+                    info,
+                    "[Trash Info]\nPath={}\nDeletionDate={}\n",
+                    percent_encode(&original),
+                    chrono::Local::now().format("%Y-%m-%dT%H:%M:%S")
+                )?;
+                return Ok(destination);
+            }
+            // Someone else trashed a file with the same name meanwhile; pick another
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+// `Trash/files/<name>` has its info at `Trash/info/<name>.trashinfo`
+fn trash_info_path(trashed_path: &Path) -> Option<PathBuf> {
+    let files_dir = trashed_path.parent()?;
+    if files_dir.file_name()? != "files" {
+        return None;
+    }
+
+    let mut info_name = trashed_path.file_name()?.to_os_string();
+    info_name.push(".");
+    info_name.push(TRASH_INFO_EXTENSION);
+    Some(files_dir.parent()?.join("info").join(info_name))
+}
+
+// Renames when possible; across filesystems the file is copied and the original removed. A
+// failed copy leaves nothing behind at `destination`.
+pub fn move_file(source: &Path, destination: &Path) -> Result<(), io::Error> {
+    match fs::rename(source, destination) {
+        Err(error) if error.kind() == io::ErrorKind::CrossesDevices => {
+            fs::copy(source, destination)
+                .and_then(|_| fs::remove_file(source))
+                .inspect_err(|_| {
+                    let _ = fs::remove_file(destination);
+                })
+        }
+        result => result,
+    }
+}
+
+// The trash may not exist yet, so its closest existing ancestor stands in for it. Without device
+// numbers both are assumed to share a volume.
+#[cfg(unix)]
+fn same_volume(path: &Path, trash_dir: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    let trash_device = trash_dir
+        .ancestors()
+        .find_map(|directory| fs::metadata(directory).ok())
+        .map(|metadata| metadata.dev());
+    match (
+        fs::metadata(path).map(|metadata| metadata.dev()),
+        trash_device,
+    ) {
+        (Ok(device), Some(trash_device)) => device == trash_device,
+        _ => true,
+    }
+}
+
+#[cfg(not(unix))]
+fn same_volume(_path: &Path, _trash_dir: &Path) -> bool {
+    true
+}
+
+// Files on another filesystem go to `$topdir/.Trash-$uid` on that filesystem, as the
+// freedesktop.org spec asks, instead of being copied into the home trash
+#[cfg(target_os = "linux")]
+fn volume_trash_dir(path: &Path) -> Result<PathBuf, io::Error> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+    let path = fs::canonicalize(path)?;
+    let device = fs::metadata(&path)?.dev();
+    let mut top_dir = path.parent().unwrap_or(&path);
+    while let Some(parent) = top_dir.parent()
+        && fs::metadata(parent)?.dev() == device
+    {
+        top_dir = parent;
+    }
+
+    // SAFETY: `getuid` takes no arguments, touches no memory of ours and cannot fail.
+    let uid = unsafe { libc::getuid() };
+    let trash_dir = top_dir.join(format!(".Trash-{uid}"));
+    match fs::DirBuilder::new().mode(0o700).create(&trash_dir) {
+        Err(error) if error.kind() != io::ErrorKind::AlreadyExists => {
+            return Err(io::Error::new(
+                error.kind(),
+                format!("cannot create trash {} ({error})", trash_dir.display()),
+            ));
+        }
+        _ => {}
+    }
+
+    // The spec requires a real directory owned by the user; anything else is not trusted
+    let metadata = fs::symlink_metadata(&trash_dir)?;
+    if !metadata.is_dir() || metadata.uid() != uid {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "{} is not a trash directory owned by you",
+                trash_dir.display()
+            ),
+        ));
+    }
+    Ok(trash_dir)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn volume_trash_dir(path: &Path) -> Result<PathBuf, io::Error> {
+    Err(io::Error::new(
+        io::ErrorKind::CrossesDevices,
+        format!(
+            "{} is on a different filesystem than the trash; use --trash-dir or --permanent",
+            path.display()
+        ),
+    ))
+}
+
+fn percent_encode(path: &Path) -> String {
+    let bytes = path_bytes(path);
+    let mut encoded = String::with_capacity(bytes.len());
+    for byte in bytes.iter().copied() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(not(unix))]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    path.to_string_lossy().into_owned().into_bytes()
+}
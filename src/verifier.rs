@@ -0,0 +1,205 @@
+use crate::duplicate_detector::DuplicateGroup;
+use crate::hasher::{Hash, HashAlgorithm, compute_file_hash};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationIssue {
+    // Same hash as the group but different bytes
+    HashCollision {
+        hash: Hash,
+        reference: PathBuf,
+        file: PathBuf,
+    },
+    // The file no longer hashes to the value it was grouped under
+    ChangedSinceHashing {
+        hash: Hash,
+        file: PathBuf,
+    },
+    Missing {
+        hash: Hash,
+        file: PathBuf,
+    },
+}
+
+impl fmt::Display for VerificationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationIssue::HashCollision {
+                hash,
+                reference,
+                file,
+            } => write!(
+                f,
+                "Hash collision in group {hash}: {} differs from {}",
+                file.display(),
+                reference.display()
+            ),
+            VerificationIssue::ChangedSinceHashing { hash, file } => write!(
+                f,
+                "File changed since hashing (group {hash}): {}",
+                file.display()
+            ),
+            VerificationIssue::Missing { hash, file } => {
+                write!(f, "File disappeared (group {hash}): {}", file.display())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Verification {
+    pub groups: BTreeMap<Hash, DuplicateGroup>,
+    pub issues: Vec<VerificationIssue>,
+}
+
+// Compares every file byte-by-byte against its group's reference file. Groups are split on
+// mismatch: files whose bytes still match the recorded hash stay grouped, other sets of
+// identical files are re-grouped under their current hash, and files left without a copy are
+// reported as issues.
+pub fn verify_groups(groups: BTreeMap<Hash, DuplicateGroup>) -> Result<Verification, io::Error> {
+    let mut verification = Verification::default();
+    let mut regrouped: Vec<(Hash, DuplicateGroup)> = Vec::new();
+
+    for (hash, group) in groups {
+        let mut classes: Vec<Vec<PathBuf>> = Vec::new();
+
+        for file in group.files {
+            if !file.exists() {
+                verification.issues.push(VerificationIssue::Missing {
+                    hash: hash.clone(),
+                    file,
+                });
+                continue;
+            }
+
+            let mut matching_class = None;
+            for (index, class) in classes.iter().enumerate() {
+                if files_equal(&class[0], &file)? {
+                    matching_class = Some(index);
+                    break;
+                }
+            }
+
+            match matching_class {
+                Some(index) => classes[index].push(file),
+                None => classes.push(vec![file]),
+            }
+        }
+
+        // Re-hash one file per content class to find which bytes the group hash belongs to
+        let mut verified: Option<Vec<PathBuf>> = None;
+        for class in classes {
+            let current_hash = compute_file_hash(&class[0], group.algorithm)?;
+            let matches_hash = current_hash == hash;
+
+            match &verified {
+                None if matches_hash => verified = Some(class),
+                Some(reference) if matches_hash => {
+                    verification.issues.extend(class.into_iter().map(|file| {
+                        VerificationIssue::HashCollision {
+                            hash: hash.clone(),
+                            reference: reference[0].clone(),
+                            file,
+                        }
+                    }));
+                }
+                _ if class.len() > 1 => {
+                    regrouped.push((current_hash, group_of(class, group.algorithm)));
+                }
+                _ => {
+                    verification.issues.extend(class.into_iter().map(|file| {
+                        VerificationIssue::ChangedSinceHashing {
+                            hash: hash.clone(),
+                            file,
+                        }
+                    }));
+                }
+            }
+        }
+
+        if let Some(files) = verified
+            && files.len() > 1
+        {
+            verification
+                .groups
+                .insert(hash, group_of(files, group.algorithm));
+        }
+    }
+
+    // Files that changed into the contents of another group join it once their bytes agree
+    for (hash, group) in regrouped {
+        match verification.groups.get_mut(&hash) {
+            None => {
+                verification.groups.insert(hash, group);
+            }
+            Some(existing) if files_equal(&existing.files[0], &group.files[0])? => {
+                existing.files.extend(group.files);
+            }
+            Some(existing) => {
+                let reference = existing.files[0].clone();
+                verification
+                    .issues
+                    .extend(
+                        group
+                            .files
+                            .into_iter()
+                            .map(|file| VerificationIssue::HashCollision {
+                                hash: hash.clone(),
+                                reference: reference.clone(),
+                                file,
+                            }),
+                    );
+            }
+        }
+    }
+
+    Ok(verification)
+}
+
+fn group_of(files: Vec<PathBuf>, algorithm: HashAlgorithm) -> DuplicateGroup {
+    let mut group = DuplicateGroup::with_algorithm(algorithm);
+    for file in files {
+        group.add_file(file);
+    }
+    group
+}
+
+pub fn files_equal(left: &Path, right: &Path) -> Result<bool, io::Error> {
+    let mut left = File::open(left)?;
+    let mut right = File::open(right)?;
+    if left.metadata()?.len() != right.metadata()?.len() {
+        return Ok(false);
+    }
+
+    let mut left_buffer = vec![0; 64 * 1024];
+    let mut right_buffer = vec![0; 64 * 1024];
+
+    loop {
+        let bytes_read = read_full(&mut left, &mut left_buffer)?;
+        if read_full(&mut right, &mut right_buffer[..bytes_read])? != bytes_read
+            || left_buffer[..bytes_read] != right_buffer[..bytes_read]
+        {
+            return Ok(false);
+        }
+        if bytes_read == 0 {
+            // Both files ended together; make sure the right one did not grow meanwhile
+            return Ok(right.read(&mut right_buffer[..1])? == 0);
+        }
+    }
+}
+
+// Like `read_exact`, but reports how much was read when the file ends early
+fn read_full(file: &mut File, buffer: &mut [u8]) -> Result<usize, io::Error> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match file.read(&mut buffer[filled..])? {
+            0 => break,
+            bytes_read => filled += bytes_read,
+        }
+    }
+    Ok(filled)
+}
@@ -1,16 +1,85 @@
-use crate::duplicate_detector::DuplicateDetector;
-use crate::file_scanner::FileScanner;
-use crate::organizer::Organizer;
+use crate::artifacts::INDEX_FILE_NAME;
+use crate::duplicate_detector::{
+    DetectionOptions, DetectionStats, DuplicateDetector, DuplicateGroup,
+};
+use crate::file_scanner::{FileScanner, ScanOptions};
+use crate::hash_cache::HashCache;
+use crate::hasher::Hash;
+use crate::journal::{self, RestoreSummary};
+use crate::keeper::KeepPolicy;
+use crate::organizer::{
+    DeleteMode, DuplicateAction, GroupDecision, OrganizeOptions, OrganizedGroup, Organizer,
+    PlannedMove, SymlinkStyle,
+};
+use crate::report::{Renderer, Report};
+use crate::text_report::TextRenderer;
+use crate::verifier::{self, VerificationIssue};
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::fmt;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkflowMode {
+    // Detect duplicates only; nothing is written or moved
+    Scan,
+    // Detect duplicates and write the index, leaving files in place
+    Report,
+    // Move duplicates into the `duplicates` folder and write the index
+    #[default]
+    Organize,
+}
+
+impl WorkflowMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            WorkflowMode::Scan => "scan",
+            WorkflowMode::Report => "report",
+            WorkflowMode::Organize => "organize",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WorkflowOptions {
+    pub mode: WorkflowMode,
+    pub scan: ScanOptions,
+    pub detection: DetectionOptions,
+    // Reuse hashes of unchanged files from `cache_file`, or the cache in the scanned root
+    pub cache: bool,
+    pub cache_file: Option<PathBuf>,
+    // Compare every group byte-by-byte before acting on it
+    pub verify: bool,
+    pub dry_run: bool,
+    pub keep: Option<KeepPolicy>,
+    pub action: DuplicateAction,
+    pub symlink_style: SymlinkStyle,
+    pub delete_mode: DeleteMode,
+    // Per-group choices from an interactive review
+    pub decisions: BTreeMap<Hash, GroupDecision>,
+}
+
+// Everything found before any file is touched, so callers can review or confirm first
+#[derive(Debug)]
+pub struct Detected {
+    pub files_scanned: usize,
+    pub detection_stats: DetectionStats,
+    pub duplicates: BTreeMap<Hash, DuplicateGroup>,
+}
+
 #[derive(Debug)]
 pub struct WorkflowSummary {
     pub files_scanned: usize,
     pub duplicate_group_count: usize,
     pub duplicates_found: bool,
+    pub detection_stats: DetectionStats,
+    pub verification_issues: Vec<VerificationIssue>,
+    pub groups: Vec<OrganizedGroup>,
+    pub plan: Vec<PlannedMove>,
+    pub report: Report,
     pub index_path: PathBuf,
     pub index_content: Option<String>,
     pub index_read_error: Option<io::Error>,
@@ -20,8 +89,13 @@ pub struct WorkflowSummary {
 pub enum WorkflowError {
     Scan { source: io::Error },
     Detect { source: io::Error },
+    Verify { source: io::Error },
+    Cache { source: io::Error },
     Organize { source: io::Error },
     IndexCreation { source: io::Error },
+    Restore { source: io::Error },
+    Confirm { source: io::Error },
+    Review { source: io::Error },
 }
 
 impl fmt::Display for WorkflowError {
@@ -29,12 +103,22 @@ impl fmt::Display for WorkflowError {
         match self {
             WorkflowError::Scan { source } => write!(f, "Error scanning files: {source}"),
             WorkflowError::Detect { source } => write!(f, "Error finding duplicates: {source}"),
+            WorkflowError::Verify { source } => write!(f, "Error verifying duplicates: {source}"),
+            WorkflowError::Cache { source } => write!(f, "Error accessing hash cache: {source}"),
             WorkflowError::Organize { source } => {
                 write!(f, "Error organizing duplicates: {source}")
             }
             WorkflowError::IndexCreation { source } => {
                 write!(f, "Error creating comprehensive index: {source}")
             }
+            WorkflowError::Restore { source } => write!(f, "Error restoring files: {source}"),
+            WorkflowError::Confirm { source } => write!(
+                f,
+                "Could not ask for confirmation ({source}); pass --yes to skip it"
+            ),
+            WorkflowError::Review { source } => {
+                write!(f, "Could not review groups interactively ({source})")
+            }
         }
     }
 }
@@ -44,69 +128,176 @@ impl std::error::Error for WorkflowError {
         match self {
             WorkflowError::Scan { source }
             | WorkflowError::Detect { source }
+            | WorkflowError::Verify { source }
+            | WorkflowError::Cache { source }
             | WorkflowError::Organize { source }
-            | WorkflowError::IndexCreation { source } => Some(source),
+            | WorkflowError::IndexCreation { source }
+            | WorkflowError::Restore { source }
+            | WorkflowError::Confirm { source }
+            | WorkflowError::Review { source } => Some(source),
         }
     }
 }
 
 pub fn execute(root_path: &str) -> Result<WorkflowSummary, WorkflowError> {
-    let scanner = FileScanner::new(root_path);
-    let files = scanner
-        .scan_files()
+    execute_with_options(root_path, &WorkflowOptions::default())
+}
+
+pub fn execute_with_options(
+    root_path: &str,
+    options: &WorkflowOptions,
+) -> Result<WorkflowSummary, WorkflowError> {
+    let detected = detect(root_path, options)?;
+    resolve(root_path, options, detected)
+}
+
+pub fn detect(root_path: &str, options: &WorkflowOptions) -> Result<Detected, WorkflowError> {
+    let scanner = FileScanner::with_options(root_path, &options.scan)
+        .map_err(|source| WorkflowError::Scan { source })?
+        .with_skip_dirs(quarantine_dirs(options));
+    let scan = scanner
+        .scan()
         .map_err(|source| WorkflowError::Scan { source })?;
+    let files = scan.files;
     let files_scanned = files.len();
 
-    let duplicates = DuplicateDetector::find_duplicates(files)
-        .map_err(|source| WorkflowError::Detect { source })?;
+    let mut detection = if options.cache {
+        let cache_path = options
+            .cache_file
+            .clone()
+            .unwrap_or_else(|| HashCache::default_path(root_path));
+        let mut cache =
+            HashCache::load(cache_path).map_err(|source| WorkflowError::Cache { source })?;
+        let detection =
+            DuplicateDetector::find_duplicates_with_cache(files, &options.detection, &mut cache)
+                .map_err(|source| WorkflowError::Detect { source })?;
+        cache
+            .save()
+            .map_err(|source| WorkflowError::Cache { source })?;
+        detection
+    } else {
+        DuplicateDetector::find_duplicates_with_options(files, &options.detection)
+            .map_err(|source| WorkflowError::Detect { source })?
+    };
+    detection.stats.link_loops = scan.link_loops;
 
-    let duplicate_group_count = duplicates.len();
-    let organizer = Organizer::new(root_path);
+    Ok(Detected {
+        files_scanned,
+        detection_stats: detection.stats,
+        duplicates: detection.groups,
+    })
+}
 
-    if duplicate_group_count == 0 {
-        organizer
-            .create_comprehensive_index(&[])
-            .map_err(|source| WorkflowError::IndexCreation { source })?;
+// Plans what `resolve` would do in organize mode without touching any file
+pub fn preview(
+    root_path: &str,
+    options: &WorkflowOptions,
+    detected: &Detected,
+) -> Result<Vec<PlannedMove>, WorkflowError> {
+    let organizer = build_organizer(
+        root_path,
+        &WorkflowOptions {
+            dry_run: true,
+            ..options.clone()
+        },
+    );
+    let groups = organizer
+        .organize_duplicates(detected.duplicates.clone())
+        .map_err(|source| WorkflowError::Organize { source })?;
 
-        let index_path = build_index_path(root_path);
-        let (index_content, index_read_error) = read_index(&index_path);
-
-        return Ok(WorkflowSummary {
-            files_scanned,
-            duplicate_group_count,
-            duplicates_found: false,
-            index_path,
-            index_content,
-            index_read_error,
-        });
-    }
+    Ok(groups.into_iter().flat_map(|group| group.moves).collect())
+}
 
-    let skip_paths = build_skip_paths();
-    let organizer = Organizer::with_skip_paths(root_path, skip_paths);
+pub fn resolve(
+    root_path: &str,
+    options: &WorkflowOptions,
+    detected: Detected,
+) -> Result<WorkflowSummary, WorkflowError> {
+    // Verified only now, so files changed while the user reviewed or confirmed the plan are
+    // caught before anything acts on them
+    let (duplicates, verification_issues) = if options.verify {
+        let verification = verifier::verify_groups(detected.duplicates)
+            .map_err(|source| WorkflowError::Verify { source })?;
+        (verification.groups, verification.issues)
+    } else {
+        (detected.duplicates, Vec::new())
+    };
 
-    let organized_groups = organizer
-        .organize_duplicates(duplicates)
-        .map_err(|source| WorkflowError::Organize { source })?;
+    // Files that changed alike during a review form groups the user never decided on
+    let reviewed_options;
+    let options = if options.decisions.is_empty() {
+        options
+    } else {
+        let mut decisions = options.decisions.clone();
+        for hash in duplicates.keys() {
+            decisions.entry(hash.clone()).or_insert(GroupDecision::Skip);
+        }
+        reviewed_options = WorkflowOptions {
+            decisions,
+            ..options.clone()
+        };
+        &reviewed_options
+    };
+    let organizer = build_organizer(root_path, options);
 
-    organizer
-        .create_comprehensive_index(&organized_groups)
-        .map_err(|source| WorkflowError::IndexCreation { source })?;
+    let groups = match options.mode {
+        WorkflowMode::Organize if !duplicates.is_empty() => organizer
+            .organize_duplicates(duplicates)
+            .map_err(|source| WorkflowError::Organize { source })?,
+        _ => organizer
+            .describe_duplicates(duplicates)
+            .map_err(|source| WorkflowError::Organize { source })?,
+    };
 
+    let report = Report::build(root_path, options, detected.files_scanned, &groups);
     let index_path = build_index_path(root_path);
-    let (index_content, index_read_error) = read_index(&index_path);
+    let (index_content, index_read_error) = if options.mode == WorkflowMode::Scan {
+        (None, None)
+    } else {
+        TextRenderer
+            .render(&report)
+            .and_then(|index_content| fs::write(&index_path, index_content))
+            .map_err(|source| WorkflowError::IndexCreation { source })?;
+        read_index(&index_path)
+    };
+
+    let plan = groups
+        .iter()
+        .flat_map(|group| group.moves.iter().cloned())
+        .collect();
 
     Ok(WorkflowSummary {
-        files_scanned,
-        duplicate_group_count: organized_groups.len(),
-        duplicates_found: true,
+        files_scanned: detected.files_scanned,
+        duplicate_group_count: groups.len(),
+        duplicates_found: !groups.is_empty(),
+        detection_stats: detected.detection_stats,
+        verification_issues,
+        groups,
+        plan,
+        report,
         index_path,
         index_content,
         index_read_error,
     })
 }
 
+pub fn restore(root_path: &str) -> Result<RestoreSummary, WorkflowError> {
+    journal::restore(root_path).map_err(|source| WorkflowError::Restore { source })
+}
+
+fn build_organizer(root_path: &str, options: &WorkflowOptions) -> Organizer {
+    Organizer::with_skip_paths(root_path, build_skip_paths(options)).with_options(OrganizeOptions {
+        dry_run: options.dry_run,
+        keep: options.keep.clone(),
+        action: options.action,
+        symlink_style: options.symlink_style,
+        delete_mode: options.delete_mode.clone(),
+        decisions: options.decisions.clone(),
+    })
+}
+
 fn build_index_path(root_path: &str) -> PathBuf {
-    Path::new(root_path).join("duplicate_files_index.txt")
+    Path::new(root_path).join(INDEX_FILE_NAME)
 }
 
 fn read_index(index_path: &Path) -> (Option<String>, Option<io::Error>) {
@@ -116,11 +307,19 @@ fn read_index(index_path: &Path) -> (Option<String>, Option<io::Error>) {
     }
 }
 
-fn build_skip_paths() -> Vec<PathBuf> {
+fn build_skip_paths(options: &WorkflowOptions) -> Vec<PathBuf> {
+    let mut skip_paths = quarantine_dirs(options);
     if let Ok(current_exe) = std::env::current_exe() {
         let canonical = fs::canonicalize(&current_exe).unwrap_or(current_exe);
-        vec![canonical]
-    } else {
-        Vec::new()
+        skip_paths.push(canonical);
+    }
+    skip_paths
+}
+
+// Quarantined copies must not be found again when the quarantine sits inside the root
+fn quarantine_dirs(options: &WorkflowOptions) -> Vec<PathBuf> {
+    match &options.delete_mode {
+        DeleteMode::Quarantine(directory) => vec![directory.clone()],
+        _ => Vec::new(),
     }
 }